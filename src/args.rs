@@ -1,20 +1,24 @@
 #[cfg(test)]
 mod tests;
 
-use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum ArgError {
     #[error("Cannot use output style = import without search mode = import")]
     IllegalStyleImport,
-    #[error("With search mode = file, output style must be auto")]
+    #[error("With search mode = file, output style must be auto, json or jsonl")]
     IllegalFileOutputMode,
 }
 
 type Result<T> = std::result::Result<T, ArgError>;
 
-#[derive(Debug, Clone, PartialEq, ValueEnum)]
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
 pub enum SearchMode {
     AllUsage,
     Class,
@@ -24,22 +28,58 @@ pub enum SearchMode {
     Smart,
 }
 
-#[derive(Debug, Clone, PartialEq, ValueEnum)]
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputStyle {
     Auto,
     Coords,
     Import,
+    Json,
+    Jsonl,
     Quickfix,
 }
 
 #[derive(Debug, Clone, PartialEq, ValueEnum)]
+pub enum Backend {
+    Auto,
+    Ag,
+    Git,
+    Native,
+    Rg,
+}
+
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
 pub enum Language {
     Auto,
+    C,
+    Cpp,
     Go,
+    Java,
     Js,
+    Kotlin,
     Python,
+    Ruby,
     Rust,
     Scala,
+    Ts,
+}
+
+/// The places import indexes can be found, in the order they're usually consulted
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum IndexLayer {
+    /// A single index file given explicitly, which replaces the others
+    #[value(skip)]
+    File,
+    /// .qf/imports in the project root
+    Project,
+    /// The user's own indexes, under XDG_DATA_HOME, XDG_CONFIG_HOME or ~/.qf/index
+    User,
+    /// System-wide indexes under XDG_DATA_DIRS
+    System,
+    /// Standard library indexes built into qf, only used if no other index knows the symbol
+    Bundled,
 }
 
 /// Find definitions, imports, or general uses of symbols in code and output their
@@ -51,7 +91,11 @@ pub enum Language {
 #[derive(Debug, Parser)]
 #[command(name = "qf")]
 #[command(version = "2.0")]
+#[command(subcommand_negates_reqs = true)]
 pub(super) struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// What to search for, default is all usages. You can search for:
     ///   - classes: including traits / objects / structs etc.
     ///   - files: just find filenames matching the term
@@ -63,15 +107,22 @@ pub(super) struct Args {
 
     /// Specify how the output should be presented; these options are mostly aimed at helping text
     /// editors like vim jump to or present the locations. import will write you a new import based
-    /// on found uses.
+    /// on found uses. json and jsonl provide full details of each hit for use by other tools, as
+    /// a single array or one object per line respectively.
     #[arg(value_enum, short, long, default_value_t=OutputStyle::Auto)]
     pub output_style: OutputStyle,
 
     /// Provide a language hint. This may be required for correct searching in some edge cases,
     /// e.g. in golang it's a hint that both classes and functions can start with a capital when
-    /// using with --mode smart
-    #[arg(value_enum, long, default_value_t=Language::Auto, help = "Provide a language hint")]
-    pub lang: Language,
+    /// using with --mode smart. Without one, the project's language is used if it has just the
+    /// one; auto searches every language regardless.
+    #[arg(value_enum, long, help = "Provide a language hint")]
+    pub lang: Option<Language>,
+
+    /// Search engine to use. By default we'll use ag or rg if either is installed, falling back
+    /// to a built-in search otherwise. git grep must be explicitly requested.
+    #[arg(value_enum, long, default_value_t=Backend::Auto)]
+    pub backend: Backend,
 
     /// Provide only the first hit
     #[arg(short = '1', long)]
@@ -81,30 +132,106 @@ pub(super) struct Args {
     #[arg(short, long)]
     pub list: bool,
 
+    /// When generating imports, show how many of the hits each import was generated from after a
+    /// tab. Imports are always listed most common first.
+    #[arg(long)]
+    pub show_counts: bool,
+
+    /// The file generated imports are for, if it isn't where they were found: imports using paths
+    /// relative to the importing file are rewritten to be correct from here
+    #[arg(long)]
+    pub target_file: Option<PathBuf>,
+
     /// Override import index file to use for fallback when generating imports
     #[arg(long)]
     pub import_index_file: Option<String>,
 
+    /// Import indexes to fall back to, in order of precedence; answers from each are merged, with
+    /// those from earlier layers listed first
+    #[arg(value_enum, long, value_delimiter = ',', default_values_t = [IndexLayer::Project, IndexLayer::User, IndexLayer::System, IndexLayer::Bundled])]
+    pub import_index_layers: Vec<IndexLayer>,
+
+    /// Languages whose bundled standard library index shouldn't be used, even with the bundled
+    /// layer enabled
+    #[arg(value_enum, long, value_delimiter = ',')]
+    pub no_bundled_index: Vec<Language>,
+
+    /// Show which index layer supplied each import found in an index
+    #[arg(long)]
+    pub show_index_layer: bool,
+
     /// Do not fall back to an index file when generating imports
     #[arg(long = "no-import-index-file", action = clap::ArgAction::SetFalse, default_value_t = true)]
     pub use_import_index_file: bool,
 
+    /// Do not answer class, function or smart searches from the project's symbol index, even if
+    /// one has been built with qf index symbols
+    #[arg(long = "no-symbol-index", action = clap::ArgAction::SetFalse, default_value_t = true)]
+    pub use_symbol_index: bool,
+
     /// Symbol to search for
-    #[arg()]
-    pub term: String,
+    #[arg(required = true)]
+    pub term: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run as a long-lived server, answering JSON-RPC search requests read from stdin, one per
+    /// line, with responses on stdout. Parsed patterns, file contents and import indexes are kept
+    /// between requests. Other options given before this command act as defaults.
+    Serve,
+    /// Run as a language server over stdio, answering definition, reference, workspace symbol
+    /// and import code action requests from any LSP client. Other options given before this
+    /// command act as defaults.
+    Lsp,
+    /// Build or update indexes used to speed up or supplement searches
+    Index {
+        #[command(subcommand)]
+        command: IndexCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum IndexCommand {
+    /// Extract every class and function definition in the project into an index under
+    /// ~/.qf/index/symbols. Once built, definition searches in the project are answered from
+    /// the index, re-reading only files which have changed since.
+    Symbols,
+    /// Build the import index for a language from the imports used in one or more source trees,
+    /// writing it to ~/.qf/index/imports/<lang>.json where it'll be used as a fallback when
+    /// generating imports
+    Build {
+        /// The language to index imports for
+        #[arg(value_enum, long)]
+        lang: Language,
+
+        /// Write the index to this file instead
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Source trees to search for imports
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+    },
+}
+
+/// Check for combinations of search mode and output style which make no sense together
+pub fn validate_style(mode: &SearchMode, style: &OutputStyle) -> Result<()> {
+    if *style == OutputStyle::Import && *mode != SearchMode::Import {
+        return Err(ArgError::IllegalStyleImport);
+    }
+
+    let file_styles = [OutputStyle::Auto, OutputStyle::Json, OutputStyle::Jsonl];
+    if *mode == SearchMode::File && !file_styles.contains(style) {
+        return Err(ArgError::IllegalFileOutputMode);
+    }
+
+    Ok(())
 }
 
 impl Args {
     /// Check for illegal argument combinations and report any errors so we can panic early
     pub fn validate(&self) -> Result<()> {
-        if self.output_style == OutputStyle::Import && self.mode != SearchMode::Import {
-            return Err(ArgError::IllegalStyleImport);
-        }
-
-        if self.mode == SearchMode::File && self.output_style != OutputStyle::Auto {
-            return Err(ArgError::IllegalFileOutputMode);
-        }
-
-        Ok(())
+        validate_style(&self.mode, &self.output_style)
     }
 }
@@ -2,23 +2,34 @@ use super::*;
 
 fn default_args() -> Args {
     Args {
+        command: None,
         mode: SearchMode::AllUsage,
         output_style: OutputStyle::Auto,
-        lang: Language::Auto,
+        lang: None,
+        backend: Backend::Auto,
         first_hit: false,
         list: false,
-        term: "OogaBooga".to_string(),
+        show_counts: false,
+        target_file: None,
+        term: Some("OogaBooga".to_string()),
         import_index_file: None,
         use_import_index_file: true,
+        import_index_layers: vec![
+            IndexLayer::Project,
+            IndexLayer::User,
+            IndexLayer::System,
+            IndexLayer::Bundled,
+        ],
+        no_bundled_index: vec![],
+        show_index_layer: false,
+        use_symbol_index: true,
     }
 }
 
 #[test]
 fn validate_success_simple_args() {
     let args = default_args();
-    let res = args.validate().unwrap();
-
-    assert_eq!(res, ());
+    assert!(args.validate().is_ok());
 }
 
 #[test]
@@ -34,7 +45,7 @@ fn validate_fail_illegal_clean_imports() {
 
 #[test]
 fn validate_fail_file_search_bad_output_style() {
-    for style in vec![OutputStyle::Coords, OutputStyle::Quickfix] {
+    for style in [OutputStyle::Coords, OutputStyle::Quickfix] {
         let args = Args {
             mode: SearchMode::File,
             output_style: style,
@@ -45,3 +56,16 @@ fn validate_fail_file_search_bad_output_style() {
         assert_eq!(res, ArgError::IllegalFileOutputMode);
     }
 }
+
+#[test]
+fn validate_success_file_search_json() {
+    for style in [OutputStyle::Json, OutputStyle::Jsonl] {
+        let args = Args {
+            mode: SearchMode::File,
+            output_style: style,
+            ..default_args()
+        };
+
+        assert!(args.validate().is_ok());
+    }
+}
@@ -0,0 +1,220 @@
+mod ag;
+mod cache;
+mod git;
+#[cfg(test)]
+mod memory;
+mod native;
+mod rg;
+#[cfg(test)]
+mod tests;
+
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process::Output;
+use std::rc::Rc;
+use std::string::FromUtf8Error;
+
+use ignore::types::{Types, TypesBuilder};
+use regex::Regex;
+use thiserror::Error;
+
+use crate::args::{Backend, Language};
+use crate::search::Dialect;
+
+pub use crate::backend::ag::Ag;
+pub use crate::backend::cache::Cached;
+pub use crate::backend::git::GitGrep;
+#[cfg(test)]
+pub use crate::backend::memory::Memory;
+pub use crate::backend::native::Native;
+pub use crate::backend::rg::Rg;
+
+#[derive(Error, Debug)]
+pub enum BackendError {
+    #[error("IO error while searching: {0}")]
+    IO(#[from] io::Error),
+    #[error("Non-utf8 output read from search process")]
+    Utf8(#[from] FromUtf8Error),
+    #[error("Error walking the source tree: {0}")]
+    Walk(#[from] ignore::Error),
+    #[error("Invalid search pattern: {0}")]
+    Pattern(#[from] regex::Error),
+    #[error("{0} failed: {1}")]
+    Engine(&'static str, String),
+}
+
+pub type Result<T> = std::result::Result<T, BackendError>;
+
+/// An engine capable of running a search pattern over the project
+///
+/// Regular searches produce one line per hit in the form filename\0line:col:text, as ag and rg
+/// do with --null, so that filenames can safely contain colons (git grep -z separating line and
+/// col with NULs too is also accepted). Filename searches match the pattern against file paths
+/// and produce NUL-terminated paths.
+pub trait SearchBackend {
+    /// The regex dialect patterns need to be written in for this backend
+    fn dialect(&self) -> Dialect;
+
+    /// Search files in any of the given languages, or every file if none are given (or any of
+    /// them is auto)
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String>;
+
+    /// Read the content of a file named in the search results, for when a hit needs to be seen
+    /// in context
+    fn read(&self, filename: &str) -> Result<String> {
+        Ok(fs::read_to_string(filename)?)
+    }
+}
+
+/// Allow one backend to be shared between many searches, as a long-running server does
+impl<B: SearchBackend + ?Sized> SearchBackend for Rc<B> {
+    fn dialect(&self) -> Dialect {
+        (**self).dialect()
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        (**self).search(pattern, filenames, langs)
+    }
+
+    fn read(&self, filename: &str) -> Result<String> {
+        (**self).read(filename)
+    }
+}
+
+/// Allow a backend to be updated between searches while they share it, as a symbol index is
+impl<B: SearchBackend + ?Sized> SearchBackend for RefCell<B> {
+    fn dialect(&self) -> Dialect {
+        self.borrow().dialect()
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        self.borrow().search(pattern, filenames, langs)
+    }
+
+    fn read(&self, filename: &str) -> Result<String> {
+        self.borrow().read(filename)
+    }
+}
+
+/// Map a language onto the name of the matching file type known to the ignore crate (and rg,
+/// which shares its type definitions)
+fn type_name(lang: &Language) -> Option<&'static str> {
+    match *lang {
+        Language::Auto => None,
+        Language::C => Some("c"),
+        Language::Cpp => Some("cpp"),
+        Language::Go => Some("go"),
+        Language::Java => Some("java"),
+        Language::Js => Some("js"),
+        Language::Kotlin => Some("kotlin"),
+        Language::Python => Some("py"),
+        Language::Ruby => Some("ruby"),
+        Language::Rust => Some("rust"),
+        Language::Scala => Some("scala"),
+        Language::Ts => Some("ts"),
+    }
+}
+
+/// The names of the file types a search in the given languages is restricted to, or None if it
+/// isn't restricted at all
+fn type_names(langs: &[Language]) -> Option<Vec<&'static str>> {
+    let names: Option<Vec<_>> = langs.iter().map(type_name).collect();
+    names.filter(|n| !n.is_empty())
+}
+
+/// Render a path relative to the search root the way ag does, without the leading ./
+pub(crate) fn display_path(path: &Path) -> String {
+    let p = path.strip_prefix(".").unwrap_or(path);
+    p.to_string_lossy().to_string()
+}
+
+/// Build a matcher restricting a search to files of the given languages, if there are any
+pub(crate) fn file_types(langs: &[Language]) -> Result<Option<Types>> {
+    let names = match type_names(langs) {
+        Some(n) => n,
+        None => return Ok(None),
+    };
+
+    let mut types = TypesBuilder::new();
+    types.add_defaults();
+    for name in names {
+        types.select(name);
+    }
+
+    Ok(Some(types.build()?))
+}
+
+/// Write filename\0line:col:text lines for each line of content matching the pattern, for engines
+/// which search file content themselves
+pub(crate) fn grep_content(re: &Regex, filename: &str, content: &str, output: &mut String) {
+    for (i, line) in content.lines().enumerate() {
+        if let Some(m) = re.find(line) {
+            output.push_str(&format!("{}\0{}:{}:{}\n", filename, i + 1, m.start() + 1, line));
+        }
+    }
+}
+
+/// Take the output of an external search engine. Exiting with 1 means nothing matched, which
+/// isn't an error, but any other failure must not be mistaken for an empty search
+fn engine_output(name: &'static str, output: Output) -> Result<String> {
+    match output.status.code() {
+        Some(0) | Some(1) => Ok(String::from_utf8(output.stdout)?),
+        _ => Err(BackendError::Engine(
+            name,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )),
+    }
+}
+
+/// Filter a NUL-separated listing of paths down to those matching the pattern, for engines which
+/// can list files but not search their names
+pub(crate) fn filter_filenames(listing: &str, pattern: &str) -> Result<String> {
+    let re = Regex::new(pattern)?;
+
+    Ok(listing
+        .split('\0')
+        .filter(|l| !l.is_empty() && re.is_match(l))
+        .map(|l| format!("{l}\0"))
+        .collect())
+}
+
+/// Check whether an executable is available somewhere on the PATH
+fn on_path(name: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|p| p.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+/// Construct the requested backend. In auto mode we prefer ag, then rg, and finally fall back to
+/// the native search; git grep is only used when asked for, as it only works inside a repository
+pub fn get_backend(backend: &Backend) -> Box<dyn SearchBackend> {
+    match *backend {
+        Backend::Ag => Box::new(Ag::default()),
+        Backend::Git => Box::new(GitGrep::default()),
+        Backend::Native => Box::new(Native::default()),
+        Backend::Rg => Box::new(Rg::default()),
+        Backend::Auto => {
+            if on_path("ag") {
+                Box::new(Ag::default())
+            } else if on_path("rg") {
+                Box::new(Rg::default())
+            } else {
+                Box::new(Native::default())
+            }
+        }
+    }
+}
+
+/// Construct the backend for a long-running process. The native search can keep patterns and
+/// files warm between searches, which more than makes up for the lack of ag or rg, so it's
+/// preferred unless another engine is explicitly requested. Other engines at least have the
+/// files read around their hits kept warm.
+pub fn get_server_backend(backend: &Backend) -> Box<dyn SearchBackend> {
+    match *backend {
+        Backend::Auto | Backend::Native => Box::new(Native::cached()),
+        _ => Box::new(Cached::new(get_backend(backend))),
+    }
+}
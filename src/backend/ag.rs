@@ -0,0 +1,55 @@
+use std::process::Command;
+
+use crate::args::Language;
+use crate::backend::{engine_output, Result, SearchBackend};
+use crate::search::Dialect;
+
+#[derive(Clone, Debug)]
+pub struct Ag {}
+
+impl Ag {
+    pub fn default() -> Ag {
+        Ag {}
+    }
+
+    /// Get extra args to provide to ag -- primarily language, currently
+    fn lang_args(lang: &Language) -> Vec<String> {
+        match *lang {
+            Language::Auto => vec![],
+            Language::C => vec!["--cc".to_string()],
+            Language::Cpp => vec!["--cpp".to_string()],
+            Language::Go => vec!["--go".to_string()],
+            Language::Java => vec!["--java".to_string()],
+            Language::Js => vec!["--js".to_string()],
+            Language::Kotlin => vec!["--kotlin".to_string()],
+            Language::Python => vec!["--python".to_string()],
+            Language::Ruby => vec!["--ruby".to_string()],
+            Language::Rust => vec!["--rust".to_string()],
+            Language::Scala => vec!["--scala".to_string()],
+            Language::Ts => vec!["--ts".to_string()],
+        }
+    }
+}
+
+impl SearchBackend for Ag {
+    fn dialect(&self) -> Dialect {
+        Dialect::Pcre
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        let mut c = Command::new("ag");
+        c.arg("-s").arg("--column").arg("--null");
+
+        for arg in langs.iter().flat_map(Self::lang_args) {
+            c.arg(arg);
+        }
+
+        if filenames {
+            c.arg("-g");
+        }
+
+        c.arg(pattern);
+
+        engine_output("ag", c.output()?)
+    }
+}
@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use crate::args::Language;
+use crate::backend::{Result, SearchBackend};
+use crate::search::Dialect;
+
+/// File contents kept between searches by a long-running process. Contents are keyed on
+/// modification time so that edits are picked up; None marks binary files.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FileCache {
+    files: HashMap<PathBuf, (Option<SystemTime>, Option<Rc<str>>)>,
+}
+
+impl FileCache {
+    /// Read the content of a file last modified at the given time, or None if it isn't text
+    pub fn read(&mut self, path: &Path, mtime: Option<SystemTime>) -> Result<Option<Rc<str>>> {
+        if let Some((cached_mtime, content)) = self.files.get(path) {
+            if mtime.is_some() && *cached_mtime == mtime {
+                return Ok(content.clone());
+            }
+        }
+
+        let content = String::from_utf8(fs::read(path)?).ok().map(Rc::from);
+        self.files
+            .insert(path.to_path_buf(), (mtime, content.clone()));
+
+        Ok(content)
+    }
+
+    /// Read the content of a file named in the search results, as SearchBackend::read does
+    pub fn read_file(&mut self, filename: &str) -> Result<String> {
+        let path = Path::new(filename);
+        let mtime = fs::metadata(path)?.modified().ok();
+
+        match self.read(path, mtime)? {
+            Some(content) => Ok(content.to_string()),
+            None => Err(io::Error::from(io::ErrorKind::InvalidData).into()),
+        }
+    }
+}
+
+/// Keeps the files read around hits warm between searches, for a long-running process using an
+/// engine which can't keep anything itself
+pub struct Cached {
+    backend: Box<dyn SearchBackend>,
+    files: RefCell<FileCache>,
+}
+
+impl Cached {
+    pub fn new(backend: Box<dyn SearchBackend>) -> Cached {
+        Cached {
+            backend,
+            files: RefCell::new(FileCache::default()),
+        }
+    }
+}
+
+impl SearchBackend for Cached {
+    fn dialect(&self) -> Dialect {
+        self.backend.dialect()
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        self.backend.search(pattern, filenames, langs)
+    }
+
+    fn read(&self, filename: &str) -> Result<String> {
+        self.files.borrow_mut().read_file(filename)
+    }
+}
@@ -0,0 +1,73 @@
+use std::process::Command;
+
+use ignore::types::TypesBuilder;
+
+use crate::args::Language;
+use crate::backend::{engine_output, filter_filenames, type_names, Result, SearchBackend};
+use crate::search::Dialect;
+
+/// Search using git grep, covering tracked and untracked (but not ignored) files. This only
+/// works from within a git repository.
+#[derive(Clone, Debug)]
+pub struct GitGrep {}
+
+impl GitGrep {
+    pub fn default() -> GitGrep {
+        GitGrep {}
+    }
+
+    /// Restrict the search to languages by way of pathspecs matching their file extensions
+    pub(super) fn pathspecs(langs: &[Language]) -> Vec<String> {
+        let names = match type_names(langs) {
+            Some(n) => n,
+            None => return vec![],
+        };
+
+        let mut types = TypesBuilder::new();
+        types.add_defaults();
+
+        types
+            .definitions()
+            .into_iter()
+            .filter(|d| names.contains(&d.name()))
+            .flat_map(|d| d.globs().to_vec())
+            .collect()
+    }
+}
+
+impl SearchBackend for GitGrep {
+    /// PCRE support in git is a build-time option, so stick to what every git understands
+    fn dialect(&self) -> Dialect {
+        Dialect::Posix
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        let mut c = Command::new("git");
+
+        if filenames {
+            c.arg("ls-files").arg("-z").arg("--cached").arg("--others").arg("--exclude-standard");
+        } else {
+            c.arg("grep")
+                .arg("-z")
+                .arg("-n")
+                .arg("--column")
+                .arg("-I")
+                .arg("--untracked")
+                .arg("-E")
+                .arg("-e")
+                .arg(pattern);
+        }
+
+        c.arg("--").args(Self::pathspecs(langs));
+
+        let output = engine_output("git", c.output()?)?;
+
+        // File patterns are an escaped term, perhaps anchored to the end of a path as for ruby
+        // constants, and POSIX reads no differently to the regex crate for patterns that simple
+        if filenames {
+            return filter_filenames(&output, pattern);
+        }
+
+        Ok(output)
+    }
+}
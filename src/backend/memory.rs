@@ -0,0 +1,135 @@
+use std::io;
+
+use regex::Regex;
+
+use crate::args::Language;
+use crate::backend::{file_types, grep_content, Result, SearchBackend};
+use crate::search::Dialect;
+
+/// Pair a fixture's path with its content, read at compile time
+macro_rules! fixture {
+    ($path:literal) => {
+        ($path, include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path)))
+    };
+}
+
+/// A backend which searches a fixed set of files held in memory, so tests don't depend on the
+/// state of the filesystem or on any search tools being installed
+#[derive(Clone, Debug)]
+pub struct Memory {
+    files: Vec<(String, String)>,
+}
+
+impl Memory {
+    pub fn new(files: &[(&str, &str)]) -> Memory {
+        Memory {
+            files: files
+                .iter()
+                .map(|(f, content)| (f.to_string(), content.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Everything under test/fixtures which contains source code
+    pub fn fixtures() -> Memory {
+        Memory::new(&[
+            fixture!("test/fixtures/c/include/geometry.h"),
+            fixture!("test/fixtures/c/src/geometry.c"),
+            fixture!("test/fixtures/c/src/main.cpp"),
+            fixture!("test/fixtures/c/src/shapes/shape.cpp"),
+            fixture!("test/fixtures/c/src/shapes/shape.hpp"),
+            fixture!("test/fixtures/go/cache/cache.go"),
+            fixture!("test/fixtures/go/cache/cache_test.go"),
+            fixture!("test/fixtures/go/cache/config.go"),
+            fixture!("test/fixtures/go/cache/single_import.go"),
+            fixture!("test/fixtures/java/src/main/java/com/example/shop/model/Audited.java"),
+            fixture!("test/fixtures/java/src/main/java/com/example/shop/model/Money.java"),
+            fixture!("test/fixtures/java/src/main/java/com/example/shop/model/Order.java"),
+            fixture!("test/fixtures/java/src/main/java/com/example/shop/model/Status.java"),
+            fixture!(
+                "test/fixtures/java/src/main/java/com/example/shop/service/OrderRepository.java"
+            ),
+            fixture!("test/fixtures/java/src/main/java/com/example/shop/service/OrderService.java"),
+            fixture!("test/fixtures/js/lib/server.js"),
+            fixture!("test/fixtures/js/src/app.js"),
+            fixture!("test/fixtures/js/src/components/Button.js"),
+            fixture!("test/fixtures/kotlin/app/src/main/kotlin/com/example/notes/model/Note.kt"),
+            fixture!(
+                "test/fixtures/kotlin/app/src/main/kotlin/com/example/notes/ui/NoteFormatter.kt"
+            ),
+            fixture!(
+                "test/fixtures/kotlin/app/src/main/kotlin/com/example/notes/ui/NotesViewModel.kt"
+            ),
+            fixture!("test/fixtures/python/cli.py"),
+            fixture!("test/fixtures/python/client.py"),
+            fixture!("test/fixtures/python/completion.py"),
+            fixture!("test/fixtures/python/multiline.py"),
+            fixture!("test/fixtures/python/shapes.py"),
+            fixture!("test/fixtures/python/tokeniser.py"),
+            fixture!("test/fixtures/ruby/app/models/billing/account.rb"),
+            fixture!("test/fixtures/ruby/app/models/billing/status.rb"),
+            fixture!("test/fixtures/ruby/app/services/billing/invoice_sync.rb"),
+            fixture!("test/fixtures/ruby/lib/tasks/export.rake"),
+            fixture!("test/fixtures/rust/multiline.rs"),
+            fixture!("test/fixtures/rust/repo.rs"),
+            fixture!("test/fixtures/rust/steam.rs"),
+            fixture!(
+                "test/fixtures/scala/src/main/scala/com/xantoria/mmo/common/model/updates/InventoryUpdate.scala"
+            ),
+            fixture!(
+                "test/fixtures/scala/src/main/scala/com/xantoria/mmo/common/model/updates/StatSheetUpdate.scala"
+            ),
+            fixture!(
+                "test/fixtures/scala/src/main/scala/com/xantoria/mmo/common/model/updates/Update.scala"
+            ),
+            fixture!(
+                "test/fixtures/scala/src/main/scala/com/xantoria/mmo/common/updates/inventory/InventoryUpdateResult.scala"
+            ),
+            fixture!(
+                "test/fixtures/scala/src/main/scala/com/xantoria/mmo/common/updates/stats/StatUpdateResult.scala"
+            ),
+            fixture!("test/fixtures/ts/src/app.tsx"),
+            fixture!("test/fixtures/ts/src/models/user.ts"),
+        ])
+    }
+}
+
+impl SearchBackend for Memory {
+    fn dialect(&self) -> Dialect {
+        Dialect::Rust
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        let re = Regex::new(pattern)?;
+        let types = file_types(langs)?;
+        let mut output = String::new();
+
+        for (filename, content) in &self.files {
+            if let Some(t) = &types {
+                if !t.matched(filename, false).is_whitelist() {
+                    continue;
+                }
+            }
+
+            if filenames {
+                if re.is_match(filename) {
+                    output.push_str(filename);
+                    output.push('\0');
+                }
+                continue;
+            }
+
+            grep_content(&re, filename, content, &mut output);
+        }
+
+        Ok(output)
+    }
+
+    fn read(&self, filename: &str) -> Result<String> {
+        self.files
+            .iter()
+            .find(|(f, _)| f == filename)
+            .map(|(_, content)| content.clone())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound).into())
+    }
+}
@@ -0,0 +1,148 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::rc::Rc;
+
+use ignore::overrides::OverrideBuilder;
+use ignore::{DirEntry, WalkBuilder};
+use regex::Regex;
+
+use crate::args::Language;
+use crate::backend::cache::FileCache;
+use crate::backend::{display_path, file_types, grep_content, Result, SearchBackend};
+use crate::search::Dialect;
+
+/// Compiled patterns and file contents kept between searches by a long-running process
+#[derive(Clone, Debug, Default)]
+struct Cache {
+    patterns: HashMap<String, Regex>,
+    files: FileCache,
+}
+
+/// An in-process search engine which walks the tree itself, honouring .gitignore / .ignore files,
+/// and runs the patterns through the regex crate. Output is in the filename\0line:col:text form of
+/// `ag --column --null` so hits can be parsed the same way regardless of where they came from.
+#[derive(Clone, Debug)]
+pub struct Native {
+    ignores: Vec<String>,
+    cache: Option<RefCell<Cache>>,
+}
+
+impl Native {
+    #[cfg(test)]
+    pub fn new(ignores: Vec<String>) -> Native {
+        Native {
+            ignores,
+            cache: None,
+        }
+    }
+
+    pub fn default() -> Native {
+        Native {
+            ignores: vec![],
+            cache: None,
+        }
+    }
+
+    /// A native search which keeps compiled patterns and file contents warm between searches
+    pub fn cached() -> Native {
+        Native {
+            ignores: vec![],
+            cache: Some(RefCell::new(Cache::default())),
+        }
+    }
+
+    fn walker(&self, langs: &[Language]) -> Result<WalkBuilder> {
+        let mut builder = WalkBuilder::new(".");
+
+        if let Some(types) = file_types(langs)? {
+            builder.types(types);
+        }
+
+        if !self.ignores.is_empty() {
+            let mut overrides = OverrideBuilder::new(".");
+            for ignore in &self.ignores {
+                overrides.add(&format!("!{ignore}"))?;
+            }
+            builder.overrides(overrides.build()?);
+        }
+
+        Ok(builder)
+    }
+
+    fn compile(&self, pattern: &str) -> Result<Regex> {
+        let cache = match &self.cache {
+            Some(c) => c,
+            None => return Ok(Regex::new(pattern)?),
+        };
+
+        if let Some(re) = cache.borrow().patterns.get(pattern) {
+            return Ok(re.clone());
+        }
+
+        let re = Regex::new(pattern)?;
+        cache
+            .borrow_mut()
+            .patterns
+            .insert(pattern.to_string(), re.clone());
+
+        Ok(re)
+    }
+
+    /// Read the content of a file, or None if it isn't text, as ag skips binary files
+    fn content(&self, entry: &DirEntry) -> Result<Option<Rc<str>>> {
+        let cache = match &self.cache {
+            Some(c) => c,
+            None => return Ok(String::from_utf8(fs::read(entry.path())?).ok().map(Rc::from)),
+        };
+
+        let mtime = entry.metadata().ok().and_then(|m| m.modified().ok());
+
+        cache.borrow_mut().files.read(entry.path(), mtime)
+    }
+}
+
+impl SearchBackend for Native {
+    fn dialect(&self) -> Dialect {
+        Dialect::Rust
+    }
+
+    /// Search the tree for a pattern, producing filename\0line:col:text lines; if filenames is
+    /// set, match the pattern against file paths instead and list the matching paths
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        let re = self.compile(pattern)?;
+        let mut output = String::new();
+
+        for entry in self.walker(langs)?.build() {
+            let entry = entry?;
+
+            if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                continue;
+            }
+
+            let filename = display_path(entry.path());
+
+            if filenames {
+                if re.is_match(&filename) {
+                    output.push_str(&filename);
+                    output.push('\0');
+                }
+                continue;
+            }
+
+            if let Some(content) = self.content(&entry)? {
+                grep_content(&re, &filename, &content, &mut output);
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Files read around hits are kept warm along with those searched
+    fn read(&self, filename: &str) -> Result<String> {
+        match &self.cache {
+            Some(c) => c.borrow_mut().files.read_file(filename),
+            None => Ok(fs::read_to_string(filename)?),
+        }
+    }
+}
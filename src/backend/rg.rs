@@ -0,0 +1,50 @@
+use std::process::Command;
+
+use crate::args::Language;
+use crate::backend::{engine_output, filter_filenames, type_names, Result, SearchBackend};
+use crate::search::Dialect;
+
+/// Search using ripgrep
+#[derive(Clone, Debug)]
+pub struct Rg {}
+
+impl Rg {
+    pub fn default() -> Rg {
+        Rg {}
+    }
+
+    fn command(langs: &[Language]) -> Command {
+        let mut c = Command::new("rg");
+        c.arg("-s").arg("--color").arg("never").arg("--null");
+
+        for t in type_names(langs).unwrap_or_default() {
+            c.arg("-t").arg(t);
+        }
+
+        c
+    }
+}
+
+impl SearchBackend for Rg {
+    fn dialect(&self) -> Dialect {
+        Dialect::Rust
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> Result<String> {
+        let mut c = Self::command(langs);
+
+        // rg can't match on filenames directly, so list everything it would search and filter
+        if filenames {
+            let output = engine_output("rg", c.arg("--files").output()?)?;
+            return filter_filenames(&output, pattern);
+        }
+
+        c.arg("--column")
+            .arg("--no-heading")
+            .arg("--with-filename")
+            .arg("-e")
+            .arg(pattern);
+
+        engine_output("rg", c.output()?)
+    }
+}
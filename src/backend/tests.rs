@@ -0,0 +1,130 @@
+use super::*;
+
+#[test]
+fn filter_filenames_listing() {
+    let listing = "src/foo.rs\0src/bar.rs\0src/foobar.rs\0";
+    let actual = filter_filenames(listing, "foo").unwrap();
+
+    assert_eq!(actual, "src/foo.rs\0src/foobar.rs\0".to_string());
+}
+
+#[test]
+/// Search the fixtures for a definition, mimicking ag --null output
+fn native_search_fixture_line() {
+    let native = Native::new(vec!["/src".to_string()]);
+    let actual = native
+        .search("class Cli:", false, &[Language::Python])
+        .unwrap();
+
+    assert_eq!(actual, "test/fixtures/python/cli.py\x0017:1:class Cli:\n".to_string());
+}
+
+#[test]
+/// Filename searches should only list matching paths
+fn native_search_fixture_filenames() {
+    let native = Native::new(vec!["/src".to_string()]);
+    let actual = native.search(r"single_import\.go", true, &[Language::Go]).unwrap();
+
+    assert_eq!(actual, "test/fixtures/go/cache/single_import.go\0".to_string());
+}
+
+#[test]
+/// Cached files should be read again once they've been modified, and not before
+fn file_cache_keyed_on_mtime() {
+    use std::time::{Duration, SystemTime};
+
+    let path = env::temp_dir().join(format!("qf-file-cache-{}", std::process::id()));
+    let mtime = |secs| Some(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+    let mut cache = cache::FileCache::default();
+
+    fs::write(&path, "first").unwrap();
+    let first = cache.read(&path, mtime(1)).unwrap();
+
+    fs::write(&path, "second").unwrap();
+    let unchanged = cache.read(&path, mtime(1)).unwrap();
+    let changed = cache.read(&path, mtime(2)).unwrap();
+
+    fs::write(&path, [0xff, 0xfe]).unwrap();
+    let binary = cache.read(&path, mtime(3)).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(first.as_deref(), Some("first"));
+    assert_eq!(unchanged.as_deref(), Some("first"));
+    assert_eq!(changed.as_deref(), Some("second"));
+    assert_eq!(binary, None);
+}
+
+#[test]
+/// Language pathspecs for git grep should come from the shared file type definitions
+fn git_pathspecs() {
+    assert_eq!(GitGrep::pathspecs(&[Language::Rust]), vec!["*.rs".to_string()]);
+    assert_eq!(GitGrep::pathspecs(&[Language::Auto]), Vec::<String>::new());
+    assert_eq!(
+        GitGrep::pathspecs(&[Language::Go, Language::Rust]),
+        vec!["*.go".to_string(), "*.rs".to_string()]
+    );
+}
+
+/// Whether git grep can run here, which needs git installed and a checkout to search
+fn in_git_repo() -> bool {
+    std::process::Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+#[test]
+/// git grep separates everything with NULs, which the parser also accepts. Outside a checkout it
+/// can't search at all, which should be an error rather than finding nothing.
+fn git_search_fixture_line() {
+    let git = GitGrep::default();
+    let actual = git.search("class Cli:", false, &[Language::Python]);
+
+    if !in_git_repo() {
+        assert!(actual.is_err());
+        return;
+    }
+
+    assert_eq!(
+        actual.unwrap(),
+        "test/fixtures/python/cli.py\x0017\x001\x00class Cli:\n".to_string()
+    );
+}
+
+#[test]
+/// A pattern the engine rejects should fail the search, explaining why
+fn git_search_bad_pattern() {
+    let git = GitGrep::default();
+    let actual = git.search("(", false, &[Language::Python]);
+
+    if !in_git_repo() {
+        assert!(actual.is_err());
+        return;
+    }
+
+    match actual {
+        Err(BackendError::Engine(name, stderr)) => {
+            assert_eq!(name, "git");
+            assert!(!stderr.is_empty());
+        }
+        other => panic!("Expected an engine error, got {other:?}"),
+    }
+}
+
+#[test]
+/// Virtual files should be searched and filtered by language just like real ones
+fn memory_search_virtual_files() {
+    let memory = Memory::new(&[
+        ("src/lib.rs", "pub struct Potato;\nstruct Carrot;\n"),
+        ("lib/veg.py", "class Carrot:\n    pass\n"),
+    ]);
+    let actual = memory.search("struct Carrot", false, &[Language::Rust]).unwrap();
+
+    assert_eq!(actual, "src/lib.rs\x002:1:struct Carrot;\n".to_string());
+    assert_eq!(memory.search("Carrot", false, &[Language::Scala]).unwrap(), "".to_string());
+    assert_eq!(
+        memory.search("Carrot", false, &[Language::Scala, Language::Python]).unwrap(),
+        "lib/veg.py\x001:7:class Carrot:\n".to_string()
+    );
+}
@@ -2,11 +2,14 @@ mod imports;
 #[cfg(test)]
 mod tests;
 
+use std::cmp::Reverse;
 use std::iter;
+use std::path::{Path, PathBuf};
 
+use serde::Serialize;
 use thiserror::Error;
 
-use crate::args::OutputStyle;
+use crate::args::{OutputStyle, SearchMode};
 use crate::fmt::imports::generate_import;
 use crate::search::Hit;
 
@@ -18,21 +21,82 @@ pub enum FormatError {
     Pattern(String),
     #[error("Unsupported language")]
     UnsupportedLanguage,
+    #[error("Error serializing hit: {0}")]
+    Serialization(String),
+}
+
+impl From<serde_json::Error> for FormatError {
+    fn from(e: serde_json::Error) -> FormatError {
+        FormatError::Serialization(e.to_string())
+    }
 }
 
 type Result<T> = std::result::Result<T, FormatError>;
 
+/// The full details of a hit, as presented by the json output styles
+#[derive(Serialize)]
+pub struct HitRecord<'a> {
+    #[serde(flatten)]
+    hit: &'a Hit,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    import: Option<String>,
+}
+
+/// An import generated from a set of hits, and how many of the hits it was generated from
+#[derive(Clone, Debug, PartialEq)]
+pub struct RankedImport {
+    pub import: String,
+    pub count: usize,
+}
+
+/// Collapse identical imports, ranking them by how often they occur. Ties keep the order the
+/// imports were first seen in.
+pub fn rank_imports<I: IntoIterator<Item = String>>(imports: I) -> Vec<RankedImport> {
+    let mut ranked: Vec<RankedImport> = vec![];
+
+    for import in imports {
+        match ranked.iter_mut().find(|r| r.import == import) {
+            Some(r) => r.count += 1,
+            None => ranked.push(RankedImport { import, count: 1 }),
+        }
+    }
+
+    ranked.sort_by_key(|r| Reverse(r.count));
+    ranked
+}
+
 pub struct HitFormatter {
     style: OutputStyle,
+    mode: SearchMode,
+    target: Option<PathBuf>,
 }
 
 impl HitFormatter {
-    pub fn new(style: &OutputStyle) -> HitFormatter {
+    pub fn new(style: &OutputStyle, mode: &SearchMode) -> HitFormatter {
         HitFormatter {
             style: style.clone(),
+            mode: mode.clone(),
+            target: None,
         }
     }
 
+    /// Generate imports to be added to the given file, rather than to wherever the hit was found
+    pub fn with_target(mut self, target: Option<&Path>) -> HitFormatter {
+        self.target = target.map(Path::to_path_buf);
+        self
+    }
+
+    /// Collect the details of a hit for json output, including the import we'd generate from it
+    /// if we were searching for imports
+    pub fn record<'a>(&self, h: &'a Hit) -> HitRecord<'a> {
+        let import = match self.mode {
+            SearchMode::Import => generate_import(h, self.target.as_deref()).ok(),
+            _ => None,
+        };
+
+        HitRecord { hit: h, import }
+    }
+
     pub fn get_coords(h: &Hit) -> Result<[String; 3]> {
         Ok([
             h.filename.to_string(),
@@ -54,7 +118,8 @@ impl HitFormatter {
                     Self::get_coords(h)?.join(":")
                 }
             }
-            OutputStyle::Import => generate_import(h)?,
+            OutputStyle::Import => generate_import(h, self.target.as_deref())?,
+            OutputStyle::Json | OutputStyle::Jsonl => serde_json::to_string(&self.record(h))?,
             OutputStyle::Coords => Self::get_coords(h)?.join(":"),
             OutputStyle::Quickfix => Self::get_coords(h)?
                 .into_iter()
@@ -65,4 +130,38 @@ impl HitFormatter {
 
         Ok(res)
     }
+
+    /// Generate an import from each hit, collapsing identical ones and putting the most common
+    /// first. Hits we can't make sense of are skipped, so long as at least one can be used, and
+    /// hits in languages we can't write imports for are ignored altogether.
+    pub fn write_imports(&self, hits: &[Hit]) -> Result<Vec<RankedImport>> {
+        let (imports, errors): (Vec<_>, Vec<_>) = hits
+            .iter()
+            .map(|h| generate_import(h, self.target.as_deref()))
+            .filter(|i| *i != Err(FormatError::UnsupportedLanguage))
+            .partition(|i| i.is_ok());
+
+        if let (true, Some(e)) = (imports.is_empty(), errors.into_iter().next()) {
+            return Err(e.unwrap_err());
+        }
+
+        Ok(rank_imports(imports.into_iter().flatten()))
+    }
+
+    /// Format a full set of hits as lines of output: usually one per hit, but json collects
+    /// everything into a single array and imports are only given once each
+    pub fn write_all(&self, hits: &[Hit]) -> Result<Vec<String>> {
+        match self.style {
+            OutputStyle::Json => {
+                let records: Vec<HitRecord> = hits.iter().map(|h| self.record(h)).collect();
+                Ok(vec![serde_json::to_string(&records)?])
+            }
+            OutputStyle::Import => Ok(self
+                .write_imports(hits)?
+                .into_iter()
+                .map(|r| r.import)
+                .collect()),
+            _ => hits.iter().map(|h| self.write(h)).collect(),
+        }
+    }
 }
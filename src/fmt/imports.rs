@@ -1,10 +1,19 @@
+mod c;
+mod js;
+mod rust;
 #[cfg(test)]
 pub mod tests;
 
+use std::path::Path;
+
 use super::{FormatError, Result};
 
 use regex::Regex;
 
+use self::c::gen_c;
+use self::js::gen_js;
+use self::rust::gen_rust;
+
 use crate::search::{DetectedLanguage, Hit};
 
 /// Generate a python import in the format of the one found
@@ -23,26 +32,113 @@ fn gen_scala(term: &str, text: &str) -> String {
     format!("import {}.{}", &prefix, term)
 }
 
-fn gen_rust(term: &str, text: &str) -> String {
-    let r = Regex::new(r#"^\s*use\s+([\w:]+::)?[^:]+;$"#).unwrap();
-    let prefix = r.replace(text, "$1").into_owned();
+/// The name a go package is assumed to have from its import path, as goimports does: the last
+/// element of the path, skipping any major version, minus any go- prefix and anything from the
+/// first character which can't be part of an identifier
+fn go_package_name(path: &str) -> &str {
+    let version = Regex::new(r"^v\d+$").unwrap();
+    let mut elements = path.rsplit('/');
+    let mut name = elements.next().unwrap_or(path);
 
-    format!("use {}{};", &prefix, term)
+    if version.is_match(name) {
+        name = elements.next().unwrap_or(name);
+    }
+
+    let name = name.strip_prefix("go-").unwrap_or(name);
+    let end = name
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(name.len());
+
+    &name[..end]
 }
 
+/// Generate a go import from a single import or a whole import block, picking out the package
+/// the term refers to. An alias is kept if the term is the alias, as are blank and dot imports.
 fn gen_go(term: &str, text: &str) -> Result<String> {
-    let r = Regex::new(&r#"^.*"([^"]*{})""#.replace("{}", term)).unwrap();
-    let full_term = &r.captures(text)
-        .ok_or(FormatError::Pattern(format!("failed to find [{term}] in [{text}]")))?[1];
+    let r = Regex::new(r#"(?:(\w+|\.)\s+)?"([^"]*)""#).unwrap();
+
+    for caps in r.captures_iter(text) {
+        let alias = caps.get(1).map(|a| a.as_str()).filter(|a| *a != "import");
+        let path = &caps[2];
+        let named = go_package_name(path) == term;
+
+        match alias {
+            Some(a) if a == term => return Ok(format!(r#"import {a} "{path}""#)),
+            Some(a) if named && (a == "_" || a == ".") => {
+                return Ok(format!(r#"import {a} "{path}""#))
+            }
+            _ if named => return Ok(format!(r#"import "{path}""#)),
+            _ => (),
+        }
+    }
+
+    Err(FormatError::Pattern(format!(
+        "failed to find [{term}] in [{text}]"
+    )))
+}
+
+/// Generate a java import of the term from an import, which may be a static import of a member,
+/// or from the package declaration of the file the term is defined in
+fn gen_java(term: &str, text: &str) -> Result<String> {
+    let import = Regex::new(r"^\s*import\s+(static\s+)?([\w\.]+)\s*;").unwrap();
+    let package = Regex::new(r"^\s*package\s+([\w\.]+)\s*;").unwrap();
+
+    if let Some(caps) = import.captures(text) {
+        let path = &caps[2];
+
+        if path.rsplit('.').next() == Some(term) {
+            let modifier = caps.get(1).map(|_| "static ").unwrap_or("");
+            return Ok(format!("import {modifier}{path};"));
+        }
+    }
+
+    if let Some(caps) = package.captures(text) {
+        return Ok(format!("import {}.{};", &caps[1], term));
+    }
+
+    Err(FormatError::Pattern(format!(
+        "failed to find [{term}] in [{text}]"
+    )))
+}
+
+/// Generate a kotlin import of the term, keeping a rename only if the term is the new name, or
+/// from the package declaration of the file the term is defined in
+fn gen_kotlin(term: &str, text: &str) -> Result<String> {
+    let import = Regex::new(r"^\s*import\s+([\w\.]+)(?:\s+as\s+(\w+))?").unwrap();
+    let package = Regex::new(r"^\s*package\s+([\w\.]+)").unwrap();
+
+    if let Some(caps) = import.captures(text) {
+        let path = &caps[1];
+
+        match caps.get(2).map(|a| a.as_str()) {
+            Some(alias) if alias == term => return Ok(format!("import {path} as {alias}")),
+            _ if path.rsplit('.').next() == Some(term) => return Ok(format!("import {path}")),
+            _ => (),
+        }
+    }
+
+    if let Some(caps) = package.captures(text) {
+        return Ok(format!("import {}.{}", &caps[1], term));
+    }
 
-    Ok(format!(r#"import "{}""#, full_term))
+    Err(FormatError::Pattern(format!(
+        "failed to find [{term}] in [{text}]"
+    )))
 }
 
-pub(super) fn generate_import(h: &Hit) -> Result<String> {
+/// Generate an import of the hit's term from the import it was found in. If the import is for a
+/// particular file, paths relative to the file the hit was found in are rewritten where needed.
+pub(super) fn generate_import(h: &Hit, target: Option<&Path>) -> Result<String> {
     match h.lang {
+        DetectedLanguage::C | DetectedLanguage::Cpp => gen_c(&h.term, &h.text, &h.filename, target),
         DetectedLanguage::Go => gen_go(&h.term, &h.text),
+        DetectedLanguage::Java => gen_java(&h.term, &h.text),
+        DetectedLanguage::Js | DetectedLanguage::Ts => {
+            gen_js(&h.term, &h.text, &h.filename, target)
+        }
+        DetectedLanguage::Kotlin => gen_kotlin(&h.term, &h.text),
         DetectedLanguage::Python => Ok(gen_py(&h.term, &h.text)),
-        DetectedLanguage::Rust => Ok(gen_rust(&h.term, &h.text)),
+        DetectedLanguage::Rust => gen_rust(&h.term, &h.text, &h.filename),
         DetectedLanguage::Scala => Ok(gen_scala(&h.term, &h.text)),
         _ => Err(FormatError::UnsupportedLanguage),
     }
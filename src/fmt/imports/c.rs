@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests;
+
+use std::path::Path;
+
+use regex::Regex;
+
+use super::js::rebase;
+use super::{FormatError, Result};
+use crate::search::statement::is_header;
+
+/// Whether an included path names the term, as <vector> or "widget.h" name vector and widget
+fn names(path: &str, term: &str) -> bool {
+    let file = path.rsplit('/').next().unwrap_or(path);
+
+    file.split_once('.').map(|(stem, _)| stem).unwrap_or(file) == term
+}
+
+/// The path to include a header by, from the file the include is for. Without one, we can only
+/// guess at the include path: headers under an include directory are included from the nearest
+/// one, and anything else by name alone.
+fn header_path(filename: &str, target: Option<&Path>) -> String {
+    let name = Path::new(filename)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let path = match target {
+        Some(t) => rebase(&format!("./{name}"), filename, t),
+        None => match filename.rsplit_once("include/") {
+            Some((dir, path)) if dir.is_empty() || dir.ends_with('/') => path.to_string(),
+            _ => name,
+        },
+    };
+
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+/// Generate an include of the header named after the term from an existing include, or of the
+/// header the term is declared in. Quoted includes found relative to the file they're in are
+/// rewritten to work from the file the include is for; anything else (including system headers
+/// in angle brackets) is searched for on the include path, so is the same from anywhere.
+pub(super) fn gen_c(
+    term: &str,
+    text: &str,
+    filename: &str,
+    target: Option<&Path>,
+) -> Result<String> {
+    let include = Regex::new(r#"^\s*#\s*include\s*([<"])([^>"]+)[>"]"#).unwrap();
+
+    match include.captures(text) {
+        Some(caps) if names(&caps[2], term) => {
+            let path = &caps[2];
+            let found = Path::new(filename)
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(path);
+
+            match (&caps[1], target) {
+                ("\"", Some(_)) if found.is_file() => Ok(format!(
+                    "#include \"{}\"",
+                    header_path(&found.to_string_lossy(), target)
+                )),
+                ("\"", _) => Ok(format!("#include \"{path}\"")),
+                _ => Ok(format!("#include <{path}>")),
+            }
+        }
+        None if is_header(filename) => {
+            Ok(format!("#include \"{}\"", header_path(filename, target)))
+        }
+        _ => Err(FormatError::Pattern(format!(
+            "failed to find [{term}] in [{text}]"
+        ))),
+    }
+}
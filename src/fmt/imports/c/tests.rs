@@ -0,0 +1,95 @@
+use super::*;
+
+const MAIN: &str = "test/fixtures/c/src/main.cpp";
+const SHAPE_HPP: &str = "test/fixtures/c/src/shapes/shape.hpp";
+
+#[test]
+/// System headers are found on the include path, so are included the same way from anywhere
+fn gen_system_header() {
+    let actual = gen_c(
+        "memory",
+        "#include <memory>",
+        MAIN,
+        Some(Path::new(SHAPE_HPP)),
+    );
+
+    assert_eq!(actual, Ok("#include <memory>".to_string()));
+    assert_eq!(
+        gen_c("stdio", "#  include <stdio.h>", MAIN, None),
+        Ok("#include <stdio.h>".to_string())
+    );
+}
+
+#[test]
+/// Quoted includes relative to the file they were found in should be rewritten to work from the
+/// file the include is for
+fn gen_quoted_relative_to_target() {
+    let text = r#"#include "shapes/shape.hpp""#;
+    let cases = [
+        (None, "shapes/shape.hpp"),
+        (Some(MAIN), "shapes/shape.hpp"),
+        (Some("test/fixtures/c/src/shapes/shape.cpp"), "shape.hpp"),
+        (
+            Some("test/fixtures/c/test/shape_test.cpp"),
+            "../src/shapes/shape.hpp",
+        ),
+    ];
+
+    for (target, path) in cases {
+        let actual = gen_c("shape", text, MAIN, target.map(Path::new));
+
+        assert_eq!(actual, Ok(format!("#include \"{path}\"")), "{target:?}");
+    }
+}
+
+#[test]
+/// Headers included from elsewhere on the include path can't be rewritten
+fn gen_quoted_on_include_path() {
+    let actual = gen_c(
+        "config",
+        r#"#include "project/config.h""#,
+        MAIN,
+        Some(Path::new(SHAPE_HPP)),
+    );
+
+    assert_eq!(actual, Ok(r#"#include "project/config.h""#.to_string()));
+}
+
+#[test]
+/// A definition in a header is included by the header's path
+fn gen_from_header() {
+    let text = "class Circle final : public Shape {";
+
+    assert_eq!(
+        gen_c("Circle", text, SHAPE_HPP, Some(Path::new(MAIN))),
+        Ok(r#"#include "shapes/shape.hpp""#.to_string())
+    );
+}
+
+#[test]
+/// Without a file to include from, headers are included from the nearest include directory, or
+/// failing that by name, rather than from wherever we happen to be searching
+fn gen_from_header_without_target() {
+    let cases = [
+        (SHAPE_HPP, "shape.hpp"),
+        ("test/fixtures/c/include/geometry.h", "geometry.h"),
+        ("include/net/http.h", "net/http.h"),
+        ("lib/include/net/include/tls.h", "tls.h"),
+        ("src/reinclude/codec.h", "codec.h"),
+    ];
+
+    for (filename, path) in cases {
+        assert_eq!(
+            gen_c("Circle", "class Circle {", filename, None),
+            Ok(format!("#include \"{path}\"")),
+            "{filename}"
+        );
+    }
+}
+
+#[test]
+fn gen_missing_term() {
+    assert!(gen_c("vector", "#include <memory>", MAIN, None).is_err());
+    assert!(gen_c("Vector", "#include <vector>", MAIN, None).is_err());
+    assert!(gen_c("main", "int main()", MAIN, None).is_err());
+}
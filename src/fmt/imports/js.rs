@@ -0,0 +1,206 @@
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::path::{Component, Path, PathBuf};
+
+use regex::Regex;
+
+use super::{FormatError, Result};
+
+/// A single name bound by an import, possibly renamed, and in typescript possibly only a type
+struct Binding<'a> {
+    name: &'a str,
+    alias: Option<&'a str>,
+    type_only: bool,
+}
+
+impl<'a> Binding<'a> {
+    /// Parse a member of a destructuring list, e.g. X, X as Y, type X or (for require) X: Y
+    fn parse(text: &'a str, rename: &str) -> Option<Binding<'a>> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+
+        let (text, type_only) = match text.strip_prefix("type ") {
+            Some(t) if !t.trim_start().starts_with("as ") => (t.trim_start(), true),
+            _ => (text, false),
+        };
+
+        match text.split_once(rename) {
+            Some((name, alias)) => Some(Binding {
+                name: name.trim(),
+                alias: Some(alias.trim()),
+                type_only,
+            }),
+            None => Some(Binding {
+                name: text,
+                alias: None,
+                type_only,
+            }),
+        }
+    }
+
+    /// Whether this binding brings the term into scope, or would if it weren't renamed
+    fn binds(&self, term: &str) -> bool {
+        self.alias == Some(term) || self.name == term
+    }
+
+    /// Write the binding for an import of the term, dropping the alias unless it is the term
+    fn write(&self, term: &str, rename: &str) -> String {
+        let prefix = if self.type_only { "type " } else { "" };
+
+        match self.alias {
+            Some(a) if a == term => format!("{prefix}{}{rename}{a}", self.name),
+            _ => format!("{prefix}{}", self.name),
+        }
+    }
+}
+
+/// Resolve . and .. components of a path without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+
+    for c in path.components() {
+        match c {
+            Component::CurDir => (),
+            Component::ParentDir if res.file_name().is_some() => {
+                res.pop();
+            }
+            c => res.push(c),
+        }
+    }
+
+    res
+}
+
+/// The directory a file is in, as an absolute path
+fn absolute_dir(filename: &Path) -> PathBuf {
+    let dir = filename.parent().unwrap_or(Path::new(""));
+
+    normalize(&env::current_dir().unwrap_or_default().join(dir))
+}
+
+/// Rewrite a relative module specifier found in one file so that it refers to the same module
+/// from another. Bare specifiers name packages, so are the same from anywhere.
+pub(super) fn rebase(spec: &str, source: &str, target: &Path) -> String {
+    if !spec.starts_with("./") && !spec.starts_with("../") {
+        return spec.to_string();
+    }
+
+    let from = absolute_dir(target);
+    let to = normalize(&absolute_dir(Path::new(source)).join(spec));
+
+    if from == absolute_dir(Path::new(source)) {
+        return spec.to_string();
+    }
+
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts: Vec<String> = vec![];
+    if common == from.len() {
+        parts.push(".".to_string());
+    }
+    parts.extend(from[common..].iter().map(|_| "..".to_string()));
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().to_string()),
+    );
+
+    parts.join("/")
+}
+
+/// Generate an es module import of the term from an import declaration, which in typescript may
+/// import only types
+fn gen_es(term: &str, text: &str) -> Option<(String, String, String)> {
+    let r =
+        Regex::new(r#"^\s*import\s+(type\s+)?(.+?)\s+from\s+(['"])([^'"]+)['"]\s*(;?)"#).unwrap();
+    let caps = r.captures(text)?;
+    let (clause, quote, spec, semi) = (&caps[2], &caps[3], &caps[4], &caps[5]);
+    let kind = if caps.get(1).is_some() { "type " } else { "" };
+
+    // Anything in braces is a named import; what's left is a default or namespace import
+    let (named, rest) = match (clause.find('{'), clause.rfind('}')) {
+        (Some(start), Some(end)) if start < end => (
+            &clause[start + 1..end],
+            format!("{}{}", &clause[..start], &clause[end + 1..]),
+        ),
+        _ => ("", clause.to_string()),
+    };
+
+    let binding = named
+        .split(',')
+        .filter_map(|b| Binding::parse(b, " as "))
+        .find(|b| b.binds(term))
+        .map(|b| format!("{{ {} }}", b.write(term, " as ")))
+        .or_else(|| {
+            rest.split(',')
+                .map(|b| b.trim())
+                .find_map(|b| match b.strip_prefix("* as ") {
+                    Some(ns) if ns.trim() == term => Some(format!("* as {term}")),
+                    None if b == term => Some(term.to_string()),
+                    _ => None,
+                })
+        })?;
+
+    Some((
+        format!("import {kind}{binding} from {quote}"),
+        spec.to_string(),
+        format!("{quote}{semi}"),
+    ))
+}
+
+/// Generate a commonjs import of the term from a require call assigned to a variable
+fn gen_cjs(term: &str, text: &str) -> Option<(String, String, String)> {
+    let r = Regex::new(
+        r#"^\s*(const|let|var)\s+(\{[^}]*\}|\w+)\s*=\s*require\(\s*(['"])([^'"]+)['"]\s*\)\s*(;?)"#,
+    )
+    .unwrap();
+    let caps = r.captures(text)?;
+    let (keyword, target, quote, spec, semi) = (&caps[1], &caps[2], &caps[3], &caps[4], &caps[5]);
+
+    let binding = match target.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+        Some(names) => names
+            .split(',')
+            .filter_map(|b| Binding::parse(b, ":"))
+            .find(|b| b.binds(term))
+            .map(|b| format!("{{ {} }}", b.write(term, ": ")))?,
+        None if target == term => term.to_string(),
+        None => return None,
+    };
+
+    Some((
+        format!("{keyword} {binding} = require({quote}"),
+        spec.to_string(),
+        format!("{quote}){semi}"),
+    ))
+}
+
+/// Generate a javascript or typescript import of just the term, in the same style as the import it
+/// was found in: an es module import of a named, default or namespace import (of types only, if
+/// it was), or a commonjs require. If the import is for a different file, a relative module path
+/// is rewritten to work from there.
+pub(super) fn gen_js(
+    term: &str,
+    text: &str,
+    filename: &str,
+    target: Option<&Path>,
+) -> Result<String> {
+    let (head, spec, tail) =
+        gen_es(term, text)
+            .or_else(|| gen_cjs(term, text))
+            .ok_or(FormatError::Pattern(format!(
+                "failed to find [{term}] in [{text}]"
+            )))?;
+
+    let spec = match target {
+        Some(t) => rebase(&spec, filename, t),
+        None => spec,
+    };
+
+    Ok(format!("{head}{spec}{tail}"))
+}
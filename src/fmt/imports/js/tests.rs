@@ -0,0 +1,139 @@
+use super::*;
+
+/// Generate an import from an import statement found in src/app.js, for use in the same file
+fn gen(term: &str, text: &str) -> Result<String> {
+    gen_js(term, text, "src/app.js", None)
+}
+
+#[test]
+fn gen_named() {
+    let text = "import { Button, ButtonGroup } from './components/Button';";
+
+    assert_eq!(
+        gen("ButtonGroup", text),
+        Ok("import { ButtonGroup } from './components/Button';".to_string())
+    );
+}
+
+#[test]
+/// A named import renamed with `as` is kept renamed when the local name is the term, and
+/// imported under its exported name when that's the term
+fn gen_named_renamed() {
+    let text = r#"import { useState as useLocalState, useEffect } from "react""#;
+
+    assert_eq!(
+        gen("useLocalState", text),
+        Ok(r#"import { useState as useLocalState } from "react""#.to_string())
+    );
+    assert_eq!(
+        gen("useState", text),
+        Ok(r#"import { useState } from "react""#.to_string())
+    );
+}
+
+#[test]
+fn gen_default() {
+    let text = "import React, { useState } from 'react';";
+
+    assert_eq!(
+        gen("React", text),
+        Ok("import React from 'react';".to_string())
+    );
+    assert_eq!(
+        gen("useState", text),
+        Ok("import { useState } from 'react';".to_string())
+    );
+}
+
+#[test]
+fn gen_namespace() {
+    let actual = gen("path", r#"import * as path from "path";"#);
+
+    assert_eq!(actual, Ok(r#"import * as path from "path";"#.to_string()));
+}
+
+#[test]
+/// Typescript imports of types alone should stay that way
+fn gen_type_only() {
+    let text = r#"import type { User, UserId } from "./models/user";"#;
+
+    assert_eq!(
+        gen("UserId", text),
+        Ok(r#"import type { UserId } from "./models/user";"#.to_string())
+    );
+    assert_eq!(
+        gen("Role", "import { type User, Role } from './models/user';"),
+        Ok("import { Role } from './models/user';".to_string())
+    );
+    assert_eq!(
+        gen("User", "import { type User, Role } from './models/user';"),
+        Ok("import { type User } from './models/user';".to_string())
+    );
+    assert_eq!(
+        gen("Props", "import type Props from './props'"),
+        Ok("import type Props from './props'".to_string())
+    );
+}
+
+#[test]
+fn gen_require() {
+    let text = r#"const { readFile, writeFile: save } = require("fs");"#;
+
+    assert_eq!(
+        gen("readFile", text),
+        Ok(r#"const { readFile } = require("fs");"#.to_string())
+    );
+    assert_eq!(
+        gen("save", text),
+        Ok(r#"const { writeFile: save } = require("fs");"#.to_string())
+    );
+    assert_eq!(
+        gen("express", "let express = require('express')"),
+        Ok("let express = require('express')".to_string())
+    );
+}
+
+#[test]
+fn gen_missing_term() {
+    assert!(gen("Modal", "import { Button } from './components/Button';").is_err());
+    assert!(gen("fs", r#"const { readFile } = require("fs");"#).is_err());
+    assert!(gen("Button", "let Button = makeButton();").is_err());
+}
+
+#[test]
+/// Relative paths should be rewritten to work from the file the import is for
+fn gen_relative_to_target() {
+    let text = "import { Button } from './components/Button';";
+    let cases = [
+        ("src/app.js", "./components/Button"),
+        ("src/other.js", "./components/Button"),
+        ("src/pages/home.js", "../components/Button"),
+        ("src/components/Modal.js", "./Button"),
+        ("src/components/forms/Form.js", "../Button"),
+        ("lib/server.js", "../src/components/Button"),
+        ("index.js", "./src/components/Button"),
+    ];
+
+    for (target, spec) in cases {
+        let actual = gen_js("Button", text, "src/app.js", Some(Path::new(target)));
+
+        assert_eq!(
+            actual,
+            Ok(format!("import {{ Button }} from '{spec}';")),
+            "{target}"
+        );
+    }
+}
+
+#[test]
+/// Packages are found the same way from any file
+fn gen_package_from_target() {
+    let actual = gen_js(
+        "React",
+        "import React from 'react';",
+        "src/app.js",
+        Some(Path::new("lib/server.js")),
+    );
+
+    assert_eq!(actual, Ok("import React from 'react';".to_string()));
+}
@@ -0,0 +1,263 @@
+#[cfg(test)]
+mod tests;
+
+use std::path::{Component, Path};
+
+use regex::Regex;
+
+use super::{FormatError, Result};
+
+/// A rust use declaration, or any part of one following a path separator
+#[derive(Debug, PartialEq)]
+enum UseTree {
+    /// A path segment followed by the rest of the tree; a leading :: is an empty segment
+    Path(String, Box<UseTree>),
+    /// The final name imported, possibly renamed
+    Name(String, Option<String>),
+    Glob,
+    Group(Vec<UseTree>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Sep,
+    Open,
+    Close,
+    Comma,
+    Star,
+}
+
+/// Split the tree of a use declaration into tokens, up to the semicolon ending it or a comment
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let token = match c {
+            ';' => break,
+            '/' if chars.peek() == Some(&'/') => break,
+            ':' if chars.next_if_eq(&':').is_some() => Token::Sep,
+            '{' => Token::Open,
+            '}' => Token::Close,
+            ',' => Token::Comma,
+            '*' => Token::Star,
+            c if c.is_whitespace() => continue,
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '#')
+                {
+                    ident.push(c);
+                }
+                Token::Ident(ident)
+            }
+            c => {
+                return Err(FormatError::Pattern(format!(
+                    "unexpected [{c}] in [{text}]"
+                )))
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the tokens of a use declaration
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<&Token> {
+        self.pos += 1;
+        self.tokens.get(self.pos - 1)
+    }
+
+    fn error(&self) -> FormatError {
+        FormatError::Pattern(format!("malformed use declaration at {:?}", self.peek()))
+    }
+
+    fn tree(&mut self) -> Result<UseTree> {
+        match self.next() {
+            Some(Token::Sep) => Ok(UseTree::Path(String::new(), Box::new(self.tree()?))),
+            Some(Token::Star) => Ok(UseTree::Glob),
+            Some(Token::Open) => self.group(),
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+
+                match self.peek() {
+                    Some(Token::Sep) => {
+                        self.pos += 1;
+                        Ok(UseTree::Path(name, Box::new(self.tree()?)))
+                    }
+                    Some(Token::Ident(a)) if a == "as" => {
+                        self.pos += 1;
+                        match self.next() {
+                            Some(Token::Ident(alias)) => {
+                                Ok(UseTree::Name(name, Some(alias.clone())))
+                            }
+                            _ => Err(self.error()),
+                        }
+                    }
+                    _ => Ok(UseTree::Name(name, None)),
+                }
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    /// The trees in a braced group, with the opening brace already consumed
+    fn group(&mut self) -> Result<UseTree> {
+        let mut trees = vec![];
+
+        loop {
+            if self.peek() == Some(&Token::Close) {
+                self.pos += 1;
+                return Ok(UseTree::Group(trees));
+            }
+
+            trees.push(self.tree()?);
+
+            match self.next() {
+                Some(Token::Comma) => (),
+                Some(Token::Close) => return Ok(UseTree::Group(trees)),
+                _ => return Err(self.error()),
+            }
+        }
+    }
+}
+
+/// Parse a use declaration, with or without a visibility modifier
+fn parse(text: &str) -> Result<UseTree> {
+    let r = Regex::new(r"^\s*(?:pub(?:\s*\([^)]*\))?\s+)?use\s+").unwrap();
+    let start = r
+        .find(text)
+        .ok_or(FormatError::Pattern(format!(
+            "not a use declaration: [{text}]"
+        )))?
+        .end();
+
+    let mut parser = Parser {
+        tokens: tokenize(&text[start..])?,
+        pos: 0,
+    };
+    let tree = parser.tree()?;
+
+    match parser.peek() {
+        None => Ok(tree),
+        Some(_) => Err(parser.error()),
+    }
+}
+
+/// Find the path through the tree which brings the term into scope, and the name it's renamed
+/// from if it's an alias
+fn resolve(
+    tree: &UseTree,
+    term: &str,
+    prefix: &mut Vec<String>,
+) -> Option<(Vec<String>, Option<String>)> {
+    match tree {
+        UseTree::Path(segment, rest) => {
+            prefix.push(segment.to_string());
+            let res = resolve(rest, term, prefix);
+            prefix.pop();
+            res
+        }
+        UseTree::Name(name, alias) => {
+            let mut path = prefix.clone();
+            if name != "self" {
+                path.push(name.to_string());
+            }
+
+            match alias {
+                Some(a) if a == term => Some((path, Some(a.to_string()))),
+                _ if path.last().map(|p| p == term).unwrap_or(false) => Some((path, None)),
+                _ => None,
+            }
+        }
+        UseTree::Glob => None,
+        UseTree::Group(trees) => trees.iter().find_map(|t| resolve(t, term, prefix)),
+    }
+}
+
+/// The path to the module defined by a file, if it's within a crate's src directory
+fn module_path(filename: &str) -> Option<Vec<String>> {
+    let path = Path::new(filename).with_extension("");
+    let parts: Vec<_> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(p) => Some(p.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+
+    let src = parts.iter().rposition(|p| p == "src")?;
+    let mut module = parts[src + 1..].to_vec();
+
+    match module.first().map(|m| m.as_str()) {
+        Some("bin") => return Some(vec![]),
+        Some("main") | Some("lib") if module.len() == 1 => return Some(vec![]),
+        _ => (),
+    }
+    if module.last().map(|m| m == "mod").unwrap_or(false) {
+        module.pop();
+    }
+
+    Some(module)
+}
+
+/// Rewrite a path relative to the module it was used in (starting self:: or super::) to start
+/// from the crate root instead, so that it can be used from anywhere in the crate. Paths are left
+/// alone if we can't tell which module the file holds.
+fn absolute_path(path: Vec<String>, filename: &str) -> Vec<String> {
+    if !matches!(
+        path.first().map(|p| p.as_str()),
+        Some("self") | Some("super")
+    ) {
+        return path;
+    }
+
+    let mut module = match module_path(filename) {
+        Some(m) => m,
+        None => return path,
+    };
+    let mut rest = &path[..];
+
+    loop {
+        match rest.first().map(|p| p.as_str()) {
+            Some("self") => (),
+            Some("super") if module.pop().is_some() => (),
+            Some("super") => return path,
+            _ => break,
+        }
+        rest = &rest[1..];
+    }
+
+    ["crate".to_string()]
+        .into_iter()
+        .chain(module)
+        .chain(rest.iter().cloned())
+        .collect()
+}
+
+/// Generate a minimal rust import for a term from a use declaration bringing it into scope,
+/// following nested groups, renames and self imports to the exact path
+pub(super) fn gen_rust(term: &str, text: &str, filename: &str) -> Result<String> {
+    let tree = parse(text)?;
+    let (path, alias) = resolve(&tree, term, &mut vec![]).ok_or(FormatError::Pattern(format!(
+        "failed to find [{term}] in [{text}]"
+    )))?;
+    let path = absolute_path(path, filename).join("::");
+
+    match alias {
+        Some(a) => Ok(format!("use {path} as {a};")),
+        None => Ok(format!("use {path};")),
+    }
+}
@@ -0,0 +1,154 @@
+use super::*;
+
+/// Generate an import from a use declaration found in an arbitrary file outside any crate
+fn gen(term: &str, text: &str) -> Result<String> {
+    gen_rust(term, text, "arbitrary-filename.rs")
+}
+
+#[test]
+fn parse_nested_tree() {
+    let expected = UseTree::Path(
+        "a".to_string(),
+        Box::new(UseTree::Group(vec![
+            UseTree::Path(
+                "b".to_string(),
+                Box::new(UseTree::Group(vec![
+                    UseTree::Name("C".to_string(), None),
+                    UseTree::Name("D".to_string(), Some("E".to_string())),
+                ])),
+            ),
+            UseTree::Glob,
+        ])),
+    );
+    let actual = parse("use a::{b::{C, D as E}, *};");
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// Anything which isn't a complete use declaration should be rejected
+fn parse_malformed() {
+    assert!(parse("let x = 3;").is_err());
+    assert!(parse("use a::{b, c").is_err());
+    assert!(parse("use a::b c;").is_err());
+}
+
+#[test]
+fn gen_nested_group() {
+    let text = "use a::{b::{C, D}, e::F};";
+
+    assert_eq!(gen("C", text), Ok("use a::b::C;".to_string()));
+    assert_eq!(gen("D", text), Ok("use a::b::D;".to_string()));
+    assert_eq!(gen("F", text), Ok("use a::e::F;".to_string()));
+}
+
+#[test]
+/// Importing self in a group imports the module itself
+fn gen_self_in_group() {
+    let actual = gen("io", "use std::io::{self, Write};");
+
+    assert_eq!(actual, Ok("use std::io;".to_string()));
+}
+
+#[test]
+/// An `as` alias in a use tree, `self` included, should come along when the alias is the term,
+/// and the original should be imported plainly, dropping the braces around it
+fn gen_renamed() {
+    let text = "use std::fmt::{self as format, Result as FmtResult};";
+
+    assert_eq!(
+        gen("FmtResult", text),
+        Ok("use std::fmt::Result as FmtResult;".to_string())
+    );
+    assert_eq!(gen("Result", text), Ok("use std::fmt::Result;".to_string()));
+    assert_eq!(
+        gen("format", text),
+        Ok("use std::fmt as format;".to_string())
+    );
+}
+
+#[test]
+/// A glob can't tell us where a term comes from, but a name alongside it can
+fn gen_with_glob() {
+    let text = "use crate::prelude::{*, Widget};";
+
+    assert_eq!(
+        gen("Widget", text),
+        Ok("use crate::prelude::Widget;".to_string())
+    );
+    assert!(gen("Gadget", text).is_err());
+}
+
+#[test]
+fn gen_pub_use() {
+    assert_eq!(
+        gen("Widget", "pub use crate::widgets::Widget;"),
+        Ok("use crate::widgets::Widget;".to_string())
+    );
+    assert_eq!(
+        gen(
+            "Widget",
+            "    pub(crate) use self::widgets::{Gadget, Widget};"
+        ),
+        Ok("use self::widgets::Widget;".to_string())
+    );
+}
+
+#[test]
+fn gen_leading_separator() {
+    let actual = gen("Value", "use ::serde_json::Value; // trailing comment");
+
+    assert_eq!(actual, Ok("use ::serde_json::Value;".to_string()));
+}
+
+#[test]
+/// Paths relative to the module the use was found in should be made relative to the crate
+fn gen_relative_paths() {
+    let cases = [
+        (
+            "src/index/layers.rs",
+            "use self::models::Index;",
+            "use crate::index::layers::models::Index;",
+        ),
+        (
+            "src/index/layers.rs",
+            "use super::models::Index;",
+            "use crate::index::models::Index;",
+        ),
+        (
+            "./src/index/mod.rs",
+            "use super::Result;",
+            "use crate::Result;",
+        ),
+        (
+            "src/main.rs",
+            "use self::args::Args;",
+            "use crate::args::Args;",
+        ),
+        (
+            "src/a/b/c.rs",
+            "use super::super::{x::Y};",
+            "use crate::a::x::Y;",
+        ),
+        ("src/main.rs", "use super::Args;", "use super::Args;"),
+        ("tests/cli.rs", "use super::Args;", "use super::Args;"),
+    ];
+
+    for (filename, text, expected) in cases {
+        let term = text.trim_end_matches(';').rsplit(':').next().unwrap();
+        let term = term.trim_end_matches('}');
+
+        assert_eq!(
+            gen_rust(term, text, filename),
+            Ok(expected.to_string()),
+            "{filename}: {text}"
+        );
+    }
+}
+
+#[test]
+fn gen_missing_term() {
+    let actual = gen("Potato", "use stuff::{Car, Sieve};");
+
+    assert!(actual.is_err());
+}
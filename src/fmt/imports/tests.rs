@@ -24,7 +24,7 @@ fn gen_scala_single() {
     );
 
     let expected = "import com.example.foo.bar.Potato".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -38,7 +38,7 @@ fn gen_scala_first_in_group() {
     );
 
     let expected = "import com.example.foo.bar.Potato".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -52,7 +52,7 @@ fn gen_scala_last_in_group() {
     );
 
     let expected = "import com.example.foo.bar.Potato".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -66,7 +66,7 @@ fn gen_scala_mid_group() {
     );
 
     let expected = "import com.example.foo.bar.Potato".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -76,7 +76,7 @@ fn gen_python_simple_single() {
     let hit = basic_hit("my_sneks", "import my_sneks", &DetectedLanguage::Python);
 
     let expected = "import my_sneks".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -90,7 +90,7 @@ fn gen_python_simple_single_dotted() {
     );
 
     let expected = "import stuff.my_sneks".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -100,7 +100,7 @@ fn gen_python_simple_first_in_group() {
     let hit = basic_hit("sneks", "import sneks, zebras", &DetectedLanguage::Python);
 
     let expected = "import sneks".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -110,7 +110,7 @@ fn gen_python_simple_last_in_group() {
     let hit = basic_hit("sneks", "import mice, sneks", &DetectedLanguage::Python);
 
     let expected = "import sneks".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -120,7 +120,7 @@ fn gen_python_from_clause_single() {
     let hit = basic_hit("sneks", "from zoo import sneks", &DetectedLanguage::Python);
 
     let expected = "from zoo import sneks".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -134,7 +134,22 @@ fn gen_python_from_clause_mid_group() {
     );
 
     let expected = "from zoo.cages import sneks".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// Statements split over several lines arrive joined up, with their parentheses
+fn gen_python_from_clause_parenthesised() {
+    let hit = basic_hit(
+        "sneks",
+        "from zoo.cages import (aardvarks, sneks as snakes)",
+        &DetectedLanguage::Python,
+    );
+
+    let expected = "from zoo.cages import sneks".to_string();
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -148,7 +163,7 @@ fn gen_rust_single() {
     );
 
     let expected = "use crate::produce::Potato;".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -158,7 +173,7 @@ fn gen_rust_no_prefix() {
     let hit = basic_hit("stuffs", "use stuffs;", &DetectedLanguage::Rust);
 
     let expected = "use stuffs;".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -172,7 +187,7 @@ fn gen_rust_mid_group() {
     );
 
     let expected = "use stuff::Potato;".to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -186,7 +201,144 @@ fn gen_golang() {
     );
 
     let expected = r#"import "github.com/foo/bar/models""#.to_string();
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// Only the package the term refers to should be picked out of an import block
+fn gen_golang_block() {
+    let text = r#"import ("fmt" "github.com/redis/go-redis/v9" "gopkg.in/yaml.v3")"#;
+    let cases = [
+        ("fmt", r#"import "fmt""#),
+        ("redis", r#"import "github.com/redis/go-redis/v9""#),
+        ("yaml", r#"import "gopkg.in/yaml.v3""#),
+    ];
+
+    for (term, expected) in cases {
+        let hit = basic_hit(term, text, &DetectedLanguage::Go);
+
+        assert_eq!(generate_import(&hit, None), Ok(expected.to_string()), "{term}");
+    }
+}
+
+#[test]
+/// Aliases should be kept when the term is the alias, and blank and dot imports kept as they are
+fn gen_golang_aliases() {
+    let text = r#"import (m "x/models" _ "github.com/lib/pq" . "github.com/onsi/gomega")"#;
+    let cases = [
+        ("m", r#"import m "x/models""#),
+        ("models", r#"import "x/models""#),
+        ("pq", r#"import _ "github.com/lib/pq""#),
+        ("gomega", r#"import . "github.com/onsi/gomega""#),
+    ];
+
+    for (term, expected) in cases {
+        let hit = basic_hit(term, text, &DetectedLanguage::Go);
+
+        assert_eq!(generate_import(&hit, None), Ok(expected.to_string()), "{term}");
+    }
+}
+
+#[test]
+fn gen_golang_missing() {
+    let hit = basic_hit("lib", r#"import "github.com/lib/pq""#, &DetectedLanguage::Go);
+
+    assert!(generate_import(&hit, None).is_err());
+}
+
+#[test]
+fn gen_java_single() {
+    let hit = basic_hit(
+        "Order",
+        "import com.example.shop.model.Order;",
+        &DetectedLanguage::Java,
+    );
+
+    let expected = "import com.example.shop.model.Order;".to_string();
+    let actual = generate_import(&hit, None);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+fn gen_java_static() {
+    let hit = basic_hit(
+        "requireNonNull",
+        "import static java.util.Objects.requireNonNull;",
+        &DetectedLanguage::Java,
+    );
+
+    let expected = "import static java.util.Objects.requireNonNull;".to_string();
+    let actual = generate_import(&hit, None);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// Terms with no import to copy are found with the package declaration of their file
+fn gen_java_package() {
+    let hit = basic_hit(
+        "Money",
+        "package com.example.shop.model;",
+        &DetectedLanguage::Java,
+    );
+
+    let expected = "import com.example.shop.model.Money;".to_string();
+    let actual = generate_import(&hit, None);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+fn gen_java_missing() {
+    let hit = basic_hit("Order", "import java.util.*;", &DetectedLanguage::Java);
+
+    assert!(generate_import(&hit, None).is_err());
+}
+
+#[test]
+fn gen_kotlin_single() {
+    let hit = basic_hit(
+        "Note",
+        "import com.example.notes.model.Note",
+        &DetectedLanguage::Kotlin,
+    );
+
+    let expected = "import com.example.notes.model.Note".to_string();
+    let actual = generate_import(&hit, None);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// A kotlin import aliased with `as` keeps the alias when it's the term, and imports the class
+/// by its own name otherwise
+fn gen_kotlin_renamed() {
+    let text = "import com.example.notes.model.NoteId as Id";
+    let cases = [
+        ("Id", "import com.example.notes.model.NoteId as Id"),
+        ("NoteId", "import com.example.notes.model.NoteId"),
+    ];
+
+    for (term, expected) in cases {
+        let hit = basic_hit(term, text, &DetectedLanguage::Kotlin);
+
+        assert_eq!(generate_import(&hit, None), Ok(expected.to_string()), "{term}");
+    }
+}
+
+#[test]
+fn gen_kotlin_package() {
+    let hit = basic_hit(
+        "summary",
+        "package com.example.notes.ui",
+        &DetectedLanguage::Kotlin,
+    );
+
+    let expected = "import com.example.notes.ui.summary".to_string();
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Ok(expected));
 }
@@ -197,7 +349,7 @@ fn import_unsupported_language() {
     let hit = basic_hit("Potato", "#include<Potato.h>", &DetectedLanguage::Unknown);
 
     let expected = FormatError::UnsupportedLanguage;
-    let actual = generate_import(&hit);
+    let actual = generate_import(&hit, None);
 
     assert_eq!(actual, Err(expected));
 }
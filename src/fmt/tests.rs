@@ -1,6 +1,6 @@
 use super::*;
 
-use crate::args::OutputStyle;
+use crate::args::{OutputStyle, SearchMode};
 use crate::search::{DetectedLanguage, Hit};
 
 /// A typical hit for a filename search, no coordinates
@@ -30,7 +30,7 @@ fn term_hit(term: &str, text: &str) -> Hit {
 #[test]
 /// Use coordinates format if no specific format specified
 fn auto_coord_fmt() {
-    let formatter = HitFormatter::new(&OutputStyle::Auto);
+    let formatter = HitFormatter::new(&OutputStyle::Auto, &SearchMode::Class);
     let hit = term_hit("Example", "class Example");
 
     let expected = "Example.scala:1337:66".to_string();
@@ -42,7 +42,7 @@ fn auto_coord_fmt() {
 #[test]
 /// Show text only with auto format if no line/col info (filename search only)
 fn auto_coord_filename_fmt() {
-    let formatter = HitFormatter::new(&OutputStyle::Auto);
+    let formatter = HitFormatter::new(&OutputStyle::Auto, &SearchMode::Class);
     let hit = filename_hit("Example.scala");
 
     let expected = "Example.scala".to_string();
@@ -54,7 +54,7 @@ fn auto_coord_filename_fmt() {
 #[test]
 /// Show coord format if specified
 fn coord_fmt() {
-    let formatter = HitFormatter::new(&OutputStyle::Coords);
+    let formatter = HitFormatter::new(&OutputStyle::Coords, &SearchMode::Class);
     let hit = term_hit("Example", "class Example");
 
     let expected = "Example.scala:1337:66".to_string();
@@ -66,7 +66,7 @@ fn coord_fmt() {
 #[test]
 /// Error if coord format specified but coords not found
 fn coord_fmt_no_coords() {
-    let formatter = HitFormatter::new(&OutputStyle::Coords);
+    let formatter = HitFormatter::new(&OutputStyle::Coords, &SearchMode::Class);
     let hit = filename_hit("Example.scala");
 
     let expected = FormatError::MissingProperty("line number".to_string());
@@ -78,7 +78,7 @@ fn coord_fmt_no_coords() {
 #[test]
 /// Show quickfix format if specified
 fn quickfix_fmt() {
-    let formatter = HitFormatter::new(&OutputStyle::Quickfix);
+    let formatter = HitFormatter::new(&OutputStyle::Quickfix, &SearchMode::Class);
     let hit = term_hit("Example", "class Example");
 
     let expected = "Example.scala:1337:66:class Example".to_string();
@@ -90,7 +90,7 @@ fn quickfix_fmt() {
 #[test]
 /// Error if quickfix format specified but coords not found
 fn quickfix_fmt_no_coords() {
-    let formatter = HitFormatter::new(&OutputStyle::Quickfix);
+    let formatter = HitFormatter::new(&OutputStyle::Quickfix, &SearchMode::Class);
     let hit = filename_hit("Example.scala");
 
     let expected = FormatError::MissingProperty("line number".to_string());
@@ -101,7 +101,7 @@ fn quickfix_fmt_no_coords() {
 
 #[test]
 fn import_fmt_scala_single() {
-    let formatter = HitFormatter::new(&OutputStyle::Import);
+    let formatter = HitFormatter::new(&OutputStyle::Import, &SearchMode::Import);
     let hit = term_hit("Potato", "import com.example.foo.bar.Potato");
 
     let expected = "import com.example.foo.bar.Potato".to_string();
@@ -109,3 +109,169 @@ fn import_fmt_scala_single() {
 
     assert_eq!(actual, Ok(expected));
 }
+
+#[test]
+/// Imports should be written to work from the target file, if there is one
+fn import_fmt_target() {
+    let formatter = HitFormatter::new(&OutputStyle::Import, &SearchMode::Import)
+        .with_target(Some(Path::new("src/pages/Home.js")));
+    let mut hit = term_hit("Button", "import { Button } from './components/Button'");
+    hit.filename = "src/App.js".to_string();
+    hit.lang = DetectedLanguage::Js;
+
+    let expected = "import { Button } from '../components/Button'".to_string();
+    let actual = formatter.write(&hit);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// json lines should carry every detail of the hit
+fn jsonl_fmt() {
+    let formatter = HitFormatter::new(&OutputStyle::Jsonl, &SearchMode::Class);
+    let hit = term_hit("Example", "class Example");
+
+    let expected = concat!(
+        r#"{"term":"Example","filename":"Example.scala","line":1337,"col":66,"#,
+        r#""text":"class Example","lang":"scala"}"#
+    )
+    .to_string();
+    let actual = formatter.write(&hit);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// Filename hits have no coordinates, which should come out as nulls rather than an error
+fn jsonl_fmt_no_coords() {
+    let formatter = HitFormatter::new(&OutputStyle::Jsonl, &SearchMode::File);
+    let hit = filename_hit("Example.scala");
+
+    let expected = concat!(
+        r#"{"term":"Example.scala","filename":"Example.scala","line":null,"col":null,"#,
+        r#""text":"Example.scala","lang":"scala"}"#
+    )
+    .to_string();
+    let actual = formatter.write(&hit);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// The generated import should be included when searching for imports
+fn jsonl_fmt_import() {
+    let formatter = HitFormatter::new(&OutputStyle::Jsonl, &SearchMode::Import);
+    let hit = term_hit("Potato", "import com.example.foo.bar.{Potato, Zucchini}");
+
+    let expected = concat!(
+        r#"{"term":"Potato","filename":"Example.scala","line":1337,"col":66,"#,
+        r#""text":"import com.example.foo.bar.{Potato, Zucchini}","lang":"scala","#,
+        r#""import":"import com.example.foo.bar.Potato"}"#
+    )
+    .to_string();
+    let actual = formatter.write(&hit);
+
+    assert_eq!(actual, Ok(expected));
+}
+
+#[test]
+/// json should collect all hits into a single array
+fn json_fmt_all() {
+    let formatter = HitFormatter::new(&OutputStyle::Json, &SearchMode::File);
+    let hits = vec![filename_hit("A.scala"), filename_hit("B.scala")];
+
+    let actual = formatter.write_all(&hits).unwrap();
+
+    assert_eq!(actual.len(), 1);
+    let parsed: serde_json::Value = serde_json::from_str(&actual[0]).unwrap();
+    assert_eq!(parsed[0]["filename"], "A.scala");
+    assert_eq!(parsed[1]["filename"], "B.scala");
+}
+
+#[test]
+/// Other styles should produce a line per hit
+fn write_all_lines() {
+    let formatter = HitFormatter::new(&OutputStyle::Auto, &SearchMode::File);
+    let hits = vec![filename_hit("A.scala"), filename_hit("B.scala")];
+
+    let actual = formatter.write_all(&hits);
+
+    assert_eq!(actual, Ok(vec!["A.scala".to_string(), "B.scala".to_string()]));
+}
+
+#[test]
+/// Identical imports should be collapsed, with the most common first
+fn rank_imports_by_count() {
+    let imports = ["a", "b", "c", "b", "c", "b"].map(String::from);
+
+    let expected = vec![
+        RankedImport {
+            import: "b".to_string(),
+            count: 3,
+        },
+        RankedImport {
+            import: "c".to_string(),
+            count: 2,
+        },
+        RankedImport {
+            import: "a".to_string(),
+            count: 1,
+        },
+    ];
+    let actual = rank_imports(imports);
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Imports should be given once each, most common first, rather than once per hit
+fn write_all_imports_ranked() {
+    let formatter = HitFormatter::new(&OutputStyle::Import, &SearchMode::Import);
+    let hits = vec![
+        term_hit("Example", "import foo.bar.Example"),
+        term_hit("Example", "import foo.baz.Example"),
+        term_hit("Example", "import foo.baz.{Example, Other}"),
+    ];
+
+    let actual = formatter.write_all(&hits);
+
+    assert_eq!(
+        actual,
+        Ok(vec![
+            "import foo.baz.Example".to_string(),
+            "import foo.bar.Example".to_string(),
+        ])
+    );
+}
+
+#[test]
+/// Hits we can't generate an import from are skipped, unless none of them are any use
+fn write_imports_skips_failures() {
+    let formatter = HitFormatter::new(&OutputStyle::Import, &SearchMode::Import);
+    let mut good = term_hit("Example", "use foo::Example;");
+    let mut bad = term_hit("Example", "use foo::{Example,");
+    good.lang = DetectedLanguage::Rust;
+    bad.lang = DetectedLanguage::Rust;
+
+    let actual = formatter.write_imports(&[bad.clone(), good]).unwrap();
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].import, "use foo::Example;");
+
+    assert!(formatter.write_imports(&[bad]).is_err());
+}
+
+#[test]
+/// Hits in languages we can't write imports for don't count as failures, as there's nothing
+/// wrong with them
+fn write_imports_ignores_unsupported_languages() {
+    let formatter = HitFormatter::new(&OutputStyle::Import, &SearchMode::Import);
+    let mut good = term_hit("Example", "use foo::Example;");
+    let mut unsupported = term_hit("Example", "import Example");
+    good.lang = DetectedLanguage::Rust;
+    unsupported.lang = DetectedLanguage::Unknown;
+
+    assert_eq!(formatter.write_imports(&[unsupported.clone()]), Ok(vec![]));
+
+    let actual = formatter.write_imports(&[unsupported, good]).unwrap();
+    assert_eq!(actual.len(), 1);
+}
@@ -1,15 +1,21 @@
-mod models;
+pub mod imports;
+pub mod layers;
+pub mod models;
+pub mod symbols;
 #[cfg(test)]
 mod tests;
 
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 use std::io::Error as IOError;
 
-use serde_json;
 use thiserror::Error;
 
+use crate::args::{IndexLayer, Language};
+use crate::backend::BackendError;
+use crate::index::layers::{language_layers, IndexPaths, IndexedImport, LayeredImportIndex};
 use crate::index::models::ImportIndex;
 
 #[derive(Error, Debug)]
@@ -18,16 +24,28 @@ pub enum IndexError {
     IO(#[from] IOError),
     #[error("Malformed index file: JSON error: {0}")]
     Malformed(#[from] serde_json::Error),
+    #[error("Error walking the source tree: {0}")]
+    Walk(#[from] ignore::Error),
+    #[error("Error searching for imports: {0}")]
+    Backend(#[from] BackendError),
+    #[error("Imports can't be indexed for {0}")]
+    Unsupported(String),
 }
 
 type Result<T> = std::result::Result<T, IndexError>;
 
-/// Resolve a language into the absolute path to the file
-fn get_import_index_filename(lang: &str) -> Result<PathBuf> {
-    let home = env::var("HOME").ok().unwrap_or_else(|| "".to_string());
+/// The directory all of qf's indexes live under
+fn get_index_dir() -> PathBuf {
+    let home = env::var("HOME").ok().unwrap_or_default();
     let mut path = PathBuf::from(&home);
     path.push(".qf");
     path.push("index");
+    path
+}
+
+/// Resolve a language into the absolute path to the file
+fn get_import_index_filename(lang: &str) -> Result<PathBuf> {
+    let mut path = get_index_dir();
     path.push("imports");
     path.push(format!("{lang}.json"));
     Ok(path)
@@ -44,3 +62,45 @@ pub fn get_import_index(lang: &str, file: &Option<String>) -> Result<ImportIndex
 
     Ok(serde_json::from_str(&data)?)
 }
+
+/// Import indexes loaded on first use and kept for later lookups, for long-running processes
+pub struct IndexCache {
+    file: Option<String>,
+    layers: Vec<IndexLayer>,
+    no_bundled: Vec<Language>,
+    paths: IndexPaths,
+    indexes: HashMap<String, LayeredImportIndex>,
+}
+
+impl IndexCache {
+    pub fn new(
+        file: &Option<String>,
+        layers: &[IndexLayer],
+        no_bundled: &[Language],
+    ) -> IndexCache {
+        IndexCache {
+            file: file.clone(),
+            layers: layers.to_vec(),
+            no_bundled: no_bundled.to_vec(),
+            paths: IndexPaths::from_env(),
+            indexes: HashMap::new(),
+        }
+    }
+
+    /// Find the imports known for a term in a language's indexes
+    pub fn lookup(&mut self, lang: &str, term: &str) -> Result<Vec<IndexedImport>> {
+        if !self.indexes.contains_key(lang) {
+            let layers = language_layers(&self.layers, lang, &self.no_bundled);
+            let index = LayeredImportIndex::load(lang, &self.file, &layers, &self.paths)?;
+            self.indexes.insert(lang.to_string(), index);
+        }
+
+        Ok(self.indexes[lang].imports(term))
+    }
+
+    #[cfg(test)]
+    pub fn is_loaded(&self, lang: &str) -> bool {
+        self.indexes.contains_key(lang)
+    }
+}
+
@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::slice;
+
+use ignore::WalkBuilder;
+use regex::Regex;
+
+use crate::args::{Language, OutputStyle, SearchMode};
+use crate::backend::{display_path, file_types};
+use crate::fmt::HitFormatter;
+use crate::index::models::ImportIndex;
+use crate::index::{get_import_index_filename, IndexError, Result};
+use crate::search::statement::{find_statement, multiline_imports};
+use crate::search::{detect_language, get_strategy, Dialect, Hit};
+
+/// Words which can appear in an import statement without being imported themselves
+const KEYWORDS: [&str; 10] = [
+    "as", "crate", "from", "import", "pub", "self", "super", "type", "use", "_",
+];
+
+/// Collects the imports found across a set of source trees, turning each imported symbol into
+/// the import we'd generate for it with an import search
+struct ImportCollector {
+    lang: Language,
+    import_line: Regex,
+    statement: Regex,
+    keyword: Regex,
+    word: Regex,
+    patterns: HashMap<String, Regex>,
+    formatter: HitFormatter,
+    index: ImportIndex,
+}
+
+impl ImportCollector {
+    fn new(lang: &Language) -> ImportCollector {
+        let strategy = get_strategy(lang);
+        let import_line = strategy.get_definition_pattern(&SearchMode::Import, Dialect::Rust);
+
+        ImportCollector {
+            lang: lang.clone(),
+            import_line: Regex::new(&import_line).unwrap(),
+            statement: Regex::new(concat!(
+                r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:from|import|use|#\s*include)\b",
+                r"|^\s*(?:const|let|var)\s+.*=\s*require\(",
+            ))
+            .unwrap(),
+            keyword: Regex::new(r"\b(?:import|include|use)\b|^\s*(?:const|let|var)\b").unwrap(),
+            word: Regex::new(r"\w+").unwrap(),
+            patterns: HashMap::new(),
+            formatter: HitFormatter::new(&OutputStyle::Import, &SearchMode::Import),
+            index: ImportIndex::new(&format!("{:?}", lang).to_lowercase()),
+        }
+    }
+
+    /// Check whether a line is an import of the given term, exactly as an import search would
+    fn imports(&mut self, term: &str, line: &str) -> bool {
+        let lang = &self.lang;
+        let re = self.patterns.entry(term.to_string()).or_insert_with(|| {
+            let pattern = get_strategy(lang).get_pattern(&SearchMode::Import, term, Dialect::Rust);
+            Regex::new(&pattern).unwrap()
+        });
+
+        re.is_match(line)
+    }
+
+    /// Find the imports made by each statement in a file. Only the words after the import
+    /// keyword are considered, as anything before it is part of the module path (e.g. python's
+    /// from x import y), as is any alias given to an import (after as, or before the path in go).
+    /// CommonJS requires have no import keyword, so the words after their declaration are used.
+    /// Unlike a search, which is checked by eye, the line must start with the import for it to
+    /// count, so comments and strings which happen to mention one are left out of the index.
+    /// Statements split over several lines are joined up first, and hit on the line they start.
+    fn hits(&mut self, filename: &str, content: &str) -> Vec<Hit> {
+        let lang = detect_language(filename);
+        let lines: Vec<&str> = content.lines().collect();
+        let mut hits = vec![];
+        let mut i = 0;
+
+        while i < lines.len() {
+            let start = i;
+            i += 1;
+
+            if !self.statement.is_match(lines[start]) {
+                continue;
+            }
+
+            let text = match find_statement(content, start, &lang) {
+                Some(s) if multiline_imports(&lang) => {
+                    i = s.end + 1;
+                    s.text
+                }
+                _ => lines[start].to_string(),
+            };
+
+            let line = text.as_str();
+            if !self.import_line.is_match(line) {
+                continue;
+            }
+
+            let kw_start = match self.keyword.find(line) {
+                Some(m) => m.end(),
+                None => continue,
+            };
+
+            let words: Vec<_> = self.word.find_iter(&line[kw_start..]).collect();
+
+            for (j, word) in words.iter().enumerate() {
+                let term = word.as_str();
+                let rest = &line[kw_start + word.end()..];
+                let aliased = (j > 0 && words[j - 1].as_str() == "as")
+                    || (rest.starts_with(char::is_whitespace) && rest.trim_start().starts_with('"'));
+
+                if aliased || KEYWORDS.contains(&term) {
+                    continue;
+                }
+
+                if !self.imports(term, line) {
+                    continue;
+                }
+
+                hits.push(Hit {
+                    term: term.to_string(),
+                    filename: filename.to_string(),
+                    line: Some(start as u64 + 1),
+                    col: Some((kw_start + word.start()) as u64 + 1),
+                    text: line.to_string(),
+                    lang: lang.clone(),
+                });
+            }
+        }
+
+        hits
+    }
+
+    /// Record the import generated for each hit in a file
+    fn collect(&mut self, filename: &str, content: &str) {
+        for hit in self.hits(filename, content) {
+            if let Ok(import) = self.formatter.write(&hit) {
+                self.index.add(&hit.term, &import, 1);
+            }
+        }
+    }
+}
+
+/// Build an import index for a language from the imports used in the given source trees, ranking
+/// the ways each symbol is imported by how often they're used
+pub fn build_import_index(lang: &Language, paths: &[PathBuf]) -> Result<ImportIndex> {
+    // Ruby's requires load files rather than naming symbols, so there's no import to generate
+    if *lang == Language::Ruby {
+        return Err(IndexError::Unsupported(format!("{:?}", lang).to_lowercase()));
+    }
+
+    let mut collector = ImportCollector::new(lang);
+    let (first, rest) = match paths.split_first() {
+        Some(p) => p,
+        None => return Ok(collector.index),
+    };
+
+    let mut builder = WalkBuilder::new(first);
+    for path in rest {
+        builder.add(path);
+    }
+    if let Some(types) = file_types(slice::from_ref(lang))? {
+        builder.types(types);
+    }
+    builder.sort_by_file_name(|a, b| a.cmp(b));
+
+    for entry in builder.build() {
+        let entry = entry?;
+
+        if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+            continue;
+        }
+
+        if let Ok(content) = String::from_utf8(fs::read(entry.path())?) {
+            collector.collect(&display_path(entry.path()), &content);
+        }
+    }
+
+    Ok(collector.index)
+}
+
+/// Write an import index where it'll be picked up for its language, or to the given file
+pub fn write_import_index(index: &ImportIndex, file: &Option<String>) -> Result<PathBuf> {
+    let path = match file {
+        Some(f) => PathBuf::from(f),
+        None => get_import_index_filename(&index.lang)?,
+    };
+
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(index)?)?;
+
+    Ok(path)
+}
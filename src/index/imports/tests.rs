@@ -0,0 +1,206 @@
+use super::*;
+
+use std::env;
+
+use crate::index::{get_import_index, IndexError};
+
+#[test]
+fn build_import_index_rust() {
+    let paths = [PathBuf::from("test/fixtures")];
+    let actual = build_import_index(&Language::Rust, &paths).unwrap();
+
+    assert_eq!(actual.lang, "rust");
+    assert_eq!(
+        actual.imports("HashMap"),
+        vec!["use std::collections::HashMap;"]
+    );
+    assert_eq!(
+        actual.imports("Client"),
+        vec!["use tokio_postgres::Client;"]
+    );
+    assert_eq!(actual.imports("ureq"), vec!["use ureq;"]);
+
+    // thiserror's Error is imported twice, so should be preferred
+    assert_eq!(
+        actual.imports("Error"),
+        vec!["use thiserror::Error;", "use tokio_postgres::Error;"]
+    );
+
+    // Neither module paths nor aliases are imported symbols
+    assert_eq!(actual.imports("std"), Vec::<String>::new());
+    assert_eq!(actual.imports("PgError"), Vec::<String>::new());
+    assert_eq!(actual.imports("GameLibrary"), Vec::<String>::new());
+
+    // Use trees split over several lines are read as a whole
+    assert_eq!(actual.imports("Display"), vec!["use std::fmt::Display;"]);
+    assert_eq!(actual.imports("Arc"), vec!["use std::sync::Arc;"]);
+    assert_eq!(
+        actual.imports("Library"),
+        vec!["use crate::models::library::Library;"]
+    );
+}
+
+#[test]
+/// Each language should only see its own files
+fn build_import_index_other_languages() {
+    let paths = [
+        PathBuf::from("test/fixtures/scala"),
+        PathBuf::from("test/fixtures/go"),
+    ];
+
+    let scala = build_import_index(&Language::Scala, &paths).unwrap();
+    assert_eq!(
+        scala.imports("Future"),
+        vec!["import scala.concurrent.Future"]
+    );
+    assert_eq!(
+        scala.imports("ItemId"),
+        vec!["import com.xantoria.mmo.common.model.item.ItemId"]
+    );
+    assert_eq!(scala.imports("fmt"), Vec::<String>::new());
+
+    let go = build_import_index(&Language::Go, &paths).unwrap();
+    assert_eq!(go.imports("fmt"), vec![r#"import "fmt""#]);
+    assert_eq!(
+        go.imports("models"),
+        vec![r#"import "github.com/giftig/hellgo/models""#]
+    );
+    assert_eq!(
+        go.imports("redis"),
+        vec![r#"import "github.com/redis/go-redis/v9""#]
+    );
+    assert_eq!(go.imports("pq"), vec![r#"import _ "github.com/lib/pq""#]);
+    assert_eq!(go.imports("hm"), Vec::<String>::new());
+}
+
+#[test]
+fn build_import_index_python() {
+    let paths = [PathBuf::from("test/fixtures/python")];
+    let actual = build_import_index(&Language::Python, &paths).unwrap();
+
+    assert_eq!(actual.imports("os"), vec!["import os"]);
+    assert_eq!(
+        actual.imports("bookmarks"),
+        vec!["from s3_browser import bookmarks"]
+    );
+    assert_eq!(actual.imports("s3_browser"), Vec::<String>::new());
+    assert_eq!(actual.imports("SafeParser"), Vec::<String>::new());
+
+    // Imports split over several lines are read as a whole
+    assert_eq!(
+        actual.imports("S3Key"),
+        vec!["from s3_browser.paths import S3Key"]
+    );
+    assert_eq!(
+        actual.imports("human_size"),
+        vec!["from s3_browser.utils import human_size"]
+    );
+}
+
+#[test]
+fn build_import_index_missing_path() {
+    let paths = [PathBuf::from("test/fixtures/no-such-dir")];
+    let actual = build_import_index(&Language::Rust, &paths).unwrap_err();
+
+    assert!(matches!(actual, IndexError::Walk { .. }));
+}
+
+#[test]
+/// A built index should be readable by the import fallback
+fn write_import_index_roundtrip() {
+    let mut index = ImportIndex::new("rust");
+    index.add("PathBuf", "use std::path::PathBuf;", 2);
+    let file = env::temp_dir().join(format!("qf-import-index-{}.json", std::process::id()));
+    let file = file.to_string_lossy().to_string();
+
+    write_import_index(&index, &Some(file.clone())).unwrap();
+    let actual = get_import_index("rust", &Some(file.clone())).unwrap();
+    fs::remove_file(file).unwrap();
+
+    assert_eq!(actual, index);
+}
+
+#[test]
+/// Imports mentioned in comments or strings shouldn't make it into the index, but re-exports
+/// should, as they import the symbol just the same
+fn collect_import_statements_only() {
+    let mut collector = ImportCollector::new(&Language::Rust);
+    let content = r#"
+use std::fs;
+pub(crate) use crate::foo::{Bar, baz::Qux};
+// We use this to import things, e.g. use some::Thing;
+let s = "use other::Thing;";
+"#;
+
+    collector.collect("src/lib.rs", content);
+
+    assert_eq!(collector.index.entries.len(), 3);
+    assert_eq!(collector.index.imports("fs"), vec!["use std::fs;"]);
+    assert_eq!(collector.index.imports("Bar"), vec!["use crate::foo::Bar;"]);
+    assert_eq!(collector.index.imports("Qux"), vec!["use crate::foo::baz::Qux;"]);
+}
+
+#[test]
+/// Hits should be on the line their statement starts, even when it's split over several lines
+fn hits_on_statement_line() {
+    let mut collector = ImportCollector::new(&Language::Python);
+    let content = "import os\n\nfrom s3_browser.paths import (\n    S3Key,\n)\nimport sys\n";
+
+    let actual: Vec<_> = collector
+        .hits("cli.py", content)
+        .into_iter()
+        .map(|h| (h.term, h.line))
+        .collect();
+
+    assert_eq!(
+        actual,
+        vec![
+            ("os".to_string(), Some(1)),
+            ("S3Key".to_string(), Some(3)),
+            ("sys".to_string(), Some(6)),
+        ]
+    );
+}
+
+#[test]
+/// CommonJS modules are imported by assigning a require, rather than with an import statement
+fn build_import_index_js() {
+    let paths = [PathBuf::from("test/fixtures/js/lib")];
+    let actual = build_import_index(&Language::Js, &paths).unwrap();
+
+    assert_eq!(
+        actual.imports("express"),
+        vec!["const express = require('express');"]
+    );
+    assert_eq!(
+        actual.imports("readFile"),
+        vec![r#"const { readFile } = require("fs");"#]
+    );
+    assert_eq!(actual.imports("require"), Vec::<String>::new());
+    assert_eq!(actual.imports("fs"), Vec::<String>::new());
+}
+
+#[test]
+/// Headers are imported by their include, whether system or local. Local includes are relative
+/// to the file they're in, so each way of reaching the header is kept.
+fn build_import_index_c() {
+    let paths = [PathBuf::from("test/fixtures/c")];
+    let actual = build_import_index(&Language::Cpp, &paths).unwrap();
+
+    assert_eq!(actual.imports("memory"), vec!["#include <memory>"]);
+    assert_eq!(
+        actual.imports("shape"),
+        vec![r#"#include "shape.hpp""#, r#"#include "shapes/shape.hpp""#]
+    );
+    assert_eq!(actual.imports("shapes"), Vec::<String>::new());
+    assert_eq!(actual.imports("hpp"), Vec::<String>::new());
+}
+
+#[test]
+/// Ruby requires files rather than symbols, so there's nothing to index
+fn build_import_index_unsupported() {
+    let paths = [PathBuf::from("test/fixtures/ruby")];
+    let actual = build_import_index(&Language::Ruby, &paths).unwrap_err();
+
+    assert!(matches!(actual, IndexError::Unsupported(lang) if lang == "ruby"));
+}
@@ -0,0 +1,212 @@
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::args::{IndexLayer, Language};
+use crate::index::models::ImportIndex;
+use crate::index::{get_import_index, get_index_dir, Result};
+
+impl fmt::Display for IndexLayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            IndexLayer::File => "file",
+            IndexLayer::Project => "project",
+            IndexLayer::User => "user",
+            IndexLayer::System => "system",
+            IndexLayer::Bundled => "bundled",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+/// Include a bundled index from the indexes directory
+macro_rules! bundled {
+    ($lang:literal) => {
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/indexes/imports/",
+            $lang,
+            ".json"
+        ))
+    };
+}
+
+/// The standard library index built into qf for a language, if there is one
+fn get_bundled_index(lang: &str) -> Option<&'static str> {
+    match lang {
+        "go" => Some(bundled!("go")),
+        "java" => Some(bundled!("java")),
+        "kotlin" => Some(bundled!("kotlin")),
+        "python" => Some(bundled!("python")),
+        "rust" => Some(bundled!("rust")),
+        "scala" => Some(bundled!("scala")),
+        _ => None,
+    }
+}
+
+/// The layers to look for a language's indexes in, leaving out the bundled index if the language
+/// has opted out of it
+pub fn language_layers(
+    layers: &[IndexLayer],
+    lang: &str,
+    no_bundled: &[Language],
+) -> Vec<IndexLayer> {
+    let opted_out = no_bundled
+        .iter()
+        .any(|l| format!("{:?}", l).to_lowercase() == lang);
+
+    layers
+        .iter()
+        .copied()
+        .filter(|l| !(opted_out && *l == IndexLayer::Bundled))
+        .collect()
+}
+
+/// An import found in an index, along with where it was found: bundled indexes have no file
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct IndexedImport {
+    pub import: String,
+    pub layer: IndexLayer,
+    pub file: Option<PathBuf>,
+}
+
+/// The directories each layer of import indexes is looked for in
+#[derive(Clone, Debug)]
+pub struct IndexPaths {
+    pub cwd: PathBuf,
+    pub user: Vec<PathBuf>,
+    pub system: Vec<PathBuf>,
+}
+
+/// Read an XDG base directory variable, which is ignored if empty or not absolute
+fn xdg_dir(var: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+}
+
+impl IndexPaths {
+    /// Locate the index directories following the XDG base directory spec
+    pub fn from_env() -> IndexPaths {
+        let home = PathBuf::from(env::var("HOME").ok().unwrap_or_default());
+        let data_home = xdg_dir("XDG_DATA_HOME").unwrap_or_else(|| home.join(".local/share"));
+        let config_home = xdg_dir("XDG_CONFIG_HOME").unwrap_or_else(|| home.join(".config"));
+        let data_dirs = env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+        IndexPaths {
+            cwd: env::current_dir().unwrap_or_default(),
+            user: vec![
+                data_home.join("qf/imports"),
+                config_home.join("qf/imports"),
+                get_index_dir().join("imports"),
+            ],
+            system: env::split_paths(&data_dirs)
+                .filter(|p| p.is_absolute())
+                .map(|p| p.join("qf/imports"))
+                .collect(),
+        }
+    }
+
+    /// The index files for a language which exist in a layer, in order of precedence. The
+    /// project's index is the one in the nearest directory up from where we are.
+    pub fn files(&self, layer: IndexLayer, lang: &str) -> Vec<PathBuf> {
+        let filename = format!("{lang}.json");
+
+        let dirs: Vec<PathBuf> = match layer {
+            IndexLayer::File | IndexLayer::Bundled => vec![],
+            IndexLayer::Project => self
+                .cwd
+                .ancestors()
+                .map(|d| d.join(".qf/imports"))
+                .find(|d| d.join(&filename).is_file())
+                .into_iter()
+                .collect(),
+            IndexLayer::User => self.user.clone(),
+            IndexLayer::System => self.system.clone(),
+        };
+
+        dirs.into_iter()
+            .map(|d| d.join(&filename))
+            .filter(|f| f.is_file())
+            .collect()
+    }
+}
+
+/// All the import indexes for a language, in order of precedence
+#[derive(Clone, Debug)]
+pub struct LayeredImportIndex {
+    indexes: Vec<(IndexLayer, Option<PathBuf>, ImportIndex)>,
+}
+
+impl LayeredImportIndex {
+    /// Load every index for a language found in the given layers, or just the given file if
+    /// there is one. Layers with no index for the language are skipped.
+    pub fn load(
+        lang: &str,
+        file: &Option<String>,
+        layers: &[IndexLayer],
+        paths: &IndexPaths,
+    ) -> Result<LayeredImportIndex> {
+        let files: Vec<(IndexLayer, PathBuf)> = match file {
+            Some(f) => vec![(IndexLayer::File, PathBuf::from(f))],
+            None => layers
+                .iter()
+                .flat_map(|&layer| {
+                    paths
+                        .files(layer, lang)
+                        .into_iter()
+                        .map(move |f| (layer, f))
+                })
+                .collect(),
+        };
+
+        let mut indexes = vec![];
+
+        for (layer, f) in files {
+            let index = get_import_index(lang, &Some(f.to_string_lossy().to_string()))?;
+            indexes.push((layer, Some(f), index));
+        }
+
+        if file.is_none() && layers.contains(&IndexLayer::Bundled) {
+            if let Some(data) = get_bundled_index(lang) {
+                indexes.push((IndexLayer::Bundled, None, serde_json::from_str(data)?));
+            }
+        }
+
+        Ok(LayeredImportIndex { indexes })
+    }
+
+    /// The imports known for a symbol across all the indexes: those from earlier indexes come
+    /// first, and an import already supplied by an earlier index isn't repeated. The bundled
+    /// indexes are a last resort, so are overridden by any other index knowing the symbol.
+    pub fn imports(&self, term: &str) -> Vec<IndexedImport> {
+        let mut res: Vec<IndexedImport> = vec![];
+
+        for (layer, file, index) in &self.indexes {
+            if *layer == IndexLayer::Bundled && !res.is_empty() {
+                break;
+            }
+
+            for import in index.imports(term) {
+                if !res.iter().any(|r| r.import == import) {
+                    res.push(IndexedImport {
+                        import,
+                        layer: *layer,
+                        file: file.clone(),
+                    });
+                }
+            }
+        }
+
+        res
+    }
+}
@@ -0,0 +1,197 @@
+use super::*;
+
+const LAYERS: [IndexLayer; 3] = [IndexLayer::Project, IndexLayer::User, IndexLayer::System];
+
+/// Index locations within the layered fixtures, searching from a directory within the project
+fn paths() -> IndexPaths {
+    let root = PathBuf::from("test/fixtures/import-index/layers");
+
+    IndexPaths {
+        cwd: root.join("project/src"),
+        user: vec![
+            root.join("user/data/qf/imports"),
+            root.join("user/config/qf/imports"),
+        ],
+        system: vec![root.join("system/qf/imports")],
+    }
+}
+
+fn imports(index: &LayeredImportIndex, term: &str) -> Vec<(String, IndexLayer)> {
+    index
+        .imports(term)
+        .into_iter()
+        .map(|i| (i.import, i.layer))
+        .collect()
+}
+
+#[test]
+fn layer_files() {
+    let paths = paths();
+    let root = PathBuf::from("test/fixtures/import-index/layers");
+
+    assert_eq!(
+        paths.files(IndexLayer::Project, "rust"),
+        vec![root.join("project/.qf/imports/rust.json")]
+    );
+    assert_eq!(
+        paths.files(IndexLayer::User, "rust"),
+        vec![root.join("user/data/qf/imports/rust.json")]
+    );
+    assert_eq!(
+        paths.files(IndexLayer::System, "python"),
+        Vec::<PathBuf>::new()
+    );
+}
+
+#[test]
+/// Answers from every layer should be merged, earlier layers first and without repeats
+fn layered_imports_merged() {
+    let index = LayeredImportIndex::load("rust", &None, &LAYERS, &paths()).unwrap();
+
+    assert_eq!(
+        imports(&index, "Error"),
+        vec![
+            ("use crate::errors::Error;".to_string(), IndexLayer::Project),
+            ("use thiserror::Error;".to_string(), IndexLayer::User),
+            ("use std::io::Error;".to_string(), IndexLayer::System),
+        ]
+    );
+    assert_eq!(
+        imports(&index, "HashMap"),
+        vec![(
+            "use std::collections::HashMap;".to_string(),
+            IndexLayer::System
+        )]
+    );
+}
+
+#[test]
+/// The chain can be reordered or cut down
+fn layered_imports_configured() {
+    let layers = [IndexLayer::System, IndexLayer::User];
+    let index = LayeredImportIndex::load("rust", &None, &layers, &paths()).unwrap();
+
+    assert_eq!(
+        imports(&index, "Error"),
+        vec![
+            ("use std::io::Error;".to_string(), IndexLayer::System),
+            ("use thiserror::Error;".to_string(), IndexLayer::User),
+            ("use crate::errors::Error;".to_string(), IndexLayer::User),
+        ]
+    );
+}
+
+#[test]
+/// An explicitly given index file replaces the layers
+fn layered_imports_file_override() {
+    let file = Some("test/fixtures/import-index/valid.json".to_string());
+    let index = LayeredImportIndex::load("rust", &file, &LAYERS, &paths()).unwrap();
+
+    assert_eq!(imports(&index, "Error"), vec![]);
+    assert_eq!(
+        imports(&index, "PathBuf"),
+        vec![("use std::path::PathBuf".to_string(), IndexLayer::File)]
+    );
+}
+
+#[test]
+/// Having no indexes at all just means having no answers
+fn layered_imports_none() {
+    let index = LayeredImportIndex::load("go", &None, &LAYERS, &paths()).unwrap();
+
+    assert_eq!(imports(&index, "fmt"), vec![]);
+}
+
+#[test]
+/// Every bundled index should load, and hold imports which name the symbol they're for
+fn bundled_indexes_valid() {
+    for lang in ["go", "java", "kotlin", "python", "rust", "scala"] {
+        let index: ImportIndex = serde_json::from_str(get_bundled_index(lang).unwrap()).unwrap();
+
+        assert_eq!(index.lang, lang);
+        assert!(!index.entries.is_empty());
+
+        for (term, candidates) in &index.entries {
+            for c in candidates {
+                let import = c.import.trim_end_matches([';', '"']);
+                assert!(
+                    import.ends_with(&format!("::{term}"))
+                        || import.ends_with(&format!(".{term}"))
+                        || import.ends_with(&format!("/{term}"))
+                        || import.ends_with(&format!(" {term}"))
+                        || import.ends_with(&format!("\"{term}")),
+                    "{lang} {term}: {}",
+                    c.import
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn bundled_imports() {
+    let layers = [IndexLayer::Bundled];
+    let index = LayeredImportIndex::load("rust", &None, &layers, &paths()).unwrap();
+
+    assert_eq!(
+        index.imports("HashMap"),
+        vec![IndexedImport {
+            import: "use std::collections::HashMap;".to_string(),
+            layer: IndexLayer::Bundled,
+            file: None,
+        }]
+    );
+
+    let index = LayeredImportIndex::load("python", &None, &layers, &paths()).unwrap();
+    assert_eq!(
+        imports(&index, "defaultdict"),
+        vec![(
+            "from collections import defaultdict".to_string(),
+            IndexLayer::Bundled
+        )]
+    );
+
+    let index = LayeredImportIndex::load("java", &None, &layers, &paths()).unwrap();
+    assert_eq!(
+        imports(&index, "ArrayList"),
+        vec![("import java.util.ArrayList;".to_string(), IndexLayer::Bundled)]
+    );
+
+    // Kotlin has its own ArrayList, imported by default, but the rest of the JDK is still needed
+    let index = LayeredImportIndex::load("kotlin", &None, &layers, &paths()).unwrap();
+    assert_eq!(imports(&index, "ArrayList"), vec![]);
+    assert_eq!(
+        imports(&index, "Instant"),
+        vec![("import java.time.Instant".to_string(), IndexLayer::Bundled)]
+    );
+}
+
+#[test]
+/// Languages can opt out of their bundled index while keeping everything else
+fn bundled_index_opt_out() {
+    let layers = [IndexLayer::Project, IndexLayer::Bundled];
+    let no_bundled = [Language::Java];
+
+    assert_eq!(language_layers(&layers, "java", &no_bundled), vec![IndexLayer::Project]);
+    assert_eq!(language_layers(&layers, "kotlin", &no_bundled), layers.to_vec());
+    assert_eq!(language_layers(&layers, "java", &[]), layers.to_vec());
+}
+
+#[test]
+/// The bundled indexes are only used for symbols which no other index knows about
+fn bundled_imports_overridden() {
+    let layers = [IndexLayer::Project, IndexLayer::User, IndexLayer::Bundled];
+    let index = LayeredImportIndex::load("rust", &None, &layers, &paths()).unwrap();
+
+    assert_eq!(
+        imports(&index, "Error"),
+        vec![
+            ("use crate::errors::Error;".to_string(), IndexLayer::Project),
+            ("use thiserror::Error;".to_string(), IndexLayer::User),
+        ]
+    );
+    assert_eq!(
+        imports(&index, "Duration"),
+        vec![("use std::time::Duration;".to_string(), IndexLayer::Bundled)]
+    );
+}
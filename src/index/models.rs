@@ -1,9 +1,151 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 
+use crate::search::DetectedLanguage;
+
+/// The current version of the import index format. Version 1 files, which predate the version
+/// field, held a single import per symbol; they're still read, each import counting once.
+pub const IMPORT_INDEX_VERSION: u32 = 2;
+
+/// One way a symbol can be imported, along with how often it's been seen imported that way
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ImportCandidate {
+    pub import: String,
+    pub count: u64,
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(try_from = "StoredImportIndex")]
 pub struct ImportIndex {
+    pub version: u32,
     pub lang: String,
-    pub entries: HashMap<String, String>,
+    pub entries: HashMap<String, Vec<ImportCandidate>>,
+}
+
+/// An entry in an import index file of any version
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredEntry {
+    Single(String),
+    Ranked(Vec<ImportCandidate>),
+}
+
+/// An import index file of any version, to be upgraded to the current one as it's read
+#[derive(Deserialize)]
+struct StoredImportIndex {
+    #[serde(default = "StoredImportIndex::first_version")]
+    version: u32,
+    lang: String,
+    entries: HashMap<String, StoredEntry>,
+}
+
+impl StoredImportIndex {
+    fn first_version() -> u32 {
+        1
+    }
+}
+
+impl TryFrom<StoredImportIndex> for ImportIndex {
+    type Error = String;
+
+    fn try_from(stored: StoredImportIndex) -> Result<ImportIndex, String> {
+        let version = stored.version;
+
+        if version > IMPORT_INDEX_VERSION {
+            return Err(format!("unsupported import index version {version}"));
+        }
+
+        let mut index = ImportIndex::new(&stored.lang);
+
+        for (term, entry) in stored.entries {
+            let candidates = match (version, entry) {
+                (1, StoredEntry::Single(import)) => vec![ImportCandidate { import, count: 1 }],
+                (2, StoredEntry::Ranked(candidates)) => candidates,
+                _ => {
+                    return Err(format!(
+                        "entry for {term} is not valid in version {version}"
+                    ))
+                }
+            };
+
+            for c in candidates {
+                index.add(&term, &c.import, c.count);
+            }
+        }
+
+        Ok(index)
+    }
+}
+
+impl ImportIndex {
+    pub fn new(lang: &str) -> ImportIndex {
+        ImportIndex {
+            version: IMPORT_INDEX_VERSION,
+            lang: lang.to_string(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Record a number of sightings of a symbol being imported a particular way, keeping each
+    /// symbol's candidates ranked with the most common first
+    pub fn add(&mut self, term: &str, import: &str, count: u64) {
+        let candidates = self.entries.entry(term.to_string()).or_default();
+
+        match candidates.iter_mut().find(|c| c.import == import) {
+            Some(c) => c.count += count,
+            None => candidates.push(ImportCandidate {
+                import: import.to_string(),
+                count,
+            }),
+        }
+
+        candidates.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.import.cmp(&b.import)));
+    }
+
+    /// The known imports for a symbol, best first
+    pub fn imports(&self, term: &str) -> Vec<String> {
+        self.entries
+            .get(term)
+            .into_iter()
+            .flatten()
+            .map(|c| c.import.clone())
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Class,
+    Function,
+}
+
+/// A definition found in the project, along with the line it was found on so that searches can
+/// be answered without reading the file again
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub file: String,
+    pub line: u64,
+    pub col: u64,
+    pub lang: DetectedLanguage,
+    pub text: String,
+}
+
+/// The definitions in a single file, as of the time it was last modified
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct FileSymbols {
+    pub mtime: Option<SystemTime>,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SymbolIndex {
+    pub version: u32,
+    pub root: PathBuf,
+    pub files: BTreeMap<String, FileSymbols>,
 }
@@ -0,0 +1,291 @@
+#[cfg(test)]
+mod tests;
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use ignore::WalkBuilder;
+use regex::Regex;
+
+use crate::args::{Language, SearchMode};
+use crate::backend::{self, display_path, file_types, filter_filenames, SearchBackend};
+use crate::index::get_index_dir;
+use crate::index::models::{FileSymbols, Symbol, SymbolIndex, SymbolKind};
+use crate::index::Result;
+use crate::search::{detect_language, get_strategy, DetectedLanguage, Dialect};
+
+/// Bumped whenever the index format changes, so that old indexes are rebuilt rather than misread
+const VERSION: u32 = 1;
+
+/// Whether searches in a mode look for definitions, which the symbol index can answer
+pub fn indexes_mode(mode: &SearchMode) -> bool {
+    matches!(mode, SearchMode::Class | SearchMode::Function | SearchMode::Smart)
+}
+
+/// Derive a stable name for a project's index from its root directory, using FNV-1a as it won't
+/// change between builds the way std's hasher may
+fn project_key(root: &Path) -> String {
+    let hash = root
+        .to_string_lossy()
+        .bytes()
+        .fold(0xcbf29ce484222325u64, |h, b| {
+            (h ^ b as u64).wrapping_mul(0x100000001b3)
+        });
+
+    format!("{hash:016x}")
+}
+
+/// Resolve a project root into the absolute path to its symbol index
+pub fn get_symbol_index_filename(root: &Path) -> PathBuf {
+    let mut path = get_index_dir();
+    path.push("symbols");
+    path.push(format!("{}.json", project_key(root)));
+    path
+}
+
+/// Load the symbol index for a project, if one has been built with the current format
+pub fn get_symbol_index(root: &Path) -> Result<Option<SymbolIndex>> {
+    let data = match fs::read_to_string(get_symbol_index_filename(root)) {
+        Ok(d) => d,
+        Err(_) => return Ok(None),
+    };
+
+    let index: SymbolIndex = serde_json::from_str(&data)?;
+
+    Ok(Some(index).filter(|i| i.version == VERSION && i.root == root))
+}
+
+/// Write the symbol index for its project, returning where it was written
+pub fn write_symbol_index(index: &SymbolIndex) -> Result<PathBuf> {
+    let path = get_symbol_index_filename(&index.root);
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, serde_json::to_string(index)?)?;
+
+    Ok(path)
+}
+
+/// Load a project's symbol index, if it has one, bringing it up to date
+pub fn open_symbol_index(root: &Path) -> Result<Option<SymbolIndex>> {
+    let mut index = match get_symbol_index(root)? {
+        Some(i) => i,
+        None => return Ok(None),
+    };
+
+    refresh_symbol_index(&mut index)?;
+
+    Ok(Some(index))
+}
+
+/// Check the whole tree for new, changed and deleted files, saving the index if anything changed
+/// so that other processes needn't read the same files again
+pub fn refresh_symbol_index(index: &mut SymbolIndex) -> Result<()> {
+    if index.refresh(Path::new("."))? {
+        write_symbol_index(index)?;
+    }
+
+    Ok(())
+}
+
+/// A project's symbol index held open by a long-running process, to answer its definition
+/// searches for as long as it runs
+#[derive(Default)]
+pub struct SymbolCache {
+    index: Option<Rc<RefCell<SymbolIndex>>>,
+}
+
+impl SymbolCache {
+    pub fn new(index: Option<SymbolIndex>) -> SymbolCache {
+        SymbolCache {
+            index: index.map(|i| Rc::new(RefCell::new(i))),
+        }
+    }
+
+    /// The backend to answer searches in a mode from, if the index can
+    pub fn backend(&self, mode: &SearchMode) -> Option<Rc<dyn SearchBackend>> {
+        let index = self.index.as_ref().filter(|_| indexes_mode(mode))?;
+        Some(index.clone())
+    }
+
+    /// Bring the index up to date before a search in a mode it answers
+    pub fn refresh(&self, mode: &SearchMode) -> Result<()> {
+        match &self.index {
+            Some(i) if indexes_mode(mode) => refresh_symbol_index(&mut i.borrow_mut()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Pulls definitions out of source files using the class and function patterns of each
+/// language's search strategy, compiling the patterns for each language only once
+struct Extractor {
+    patterns: HashMap<DetectedLanguage, Vec<(SymbolKind, Regex)>>,
+}
+
+impl Extractor {
+    fn new() -> Extractor {
+        Extractor {
+            patterns: HashMap::new(),
+        }
+    }
+
+    fn extract(&mut self, filename: &str, content: &str, lang: &DetectedLanguage) -> Vec<Symbol> {
+        let patterns = self.patterns.entry(lang.clone()).or_insert_with(|| {
+            let strategy = get_strategy(&lang.language());
+
+            [
+                (SymbolKind::Class, SearchMode::Class),
+                (SymbolKind::Function, SearchMode::Function),
+            ]
+            .into_iter()
+            .map(|(kind, mode)| {
+                let pattern = strategy.get_definition_pattern(&mode, Dialect::Rust);
+                (kind, Regex::new(&pattern).unwrap())
+            })
+            .collect()
+        });
+
+        let mut symbols = vec![];
+
+        for (i, line) in content.lines().enumerate() {
+            for (kind, re) in patterns.iter() {
+                for caps in re.captures_iter(line) {
+                    // Patterns made of alternatives capture the name in whichever one matched
+                    let name = match caps.iter().skip(1).flatten().next() {
+                        Some(m) => m,
+                        None => continue,
+                    };
+                    let col = name.start();
+
+                    symbols.push(Symbol {
+                        name: name.as_str().to_string(),
+                        kind: *kind,
+                        file: filename.to_string(),
+                        line: i as u64 + 1,
+                        col: col as u64 + 1,
+                        lang: lang.clone(),
+                        text: line.to_string(),
+                    });
+                }
+            }
+        }
+
+        symbols
+    }
+}
+
+impl SymbolIndex {
+    pub fn new(root: &Path) -> SymbolIndex {
+        SymbolIndex {
+            version: VERSION,
+            root: root.to_path_buf(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Bring the index up to date with the source files under dir, only reading those which have
+    /// been modified since they were indexed. Returns whether anything changed.
+    pub fn refresh(&mut self, dir: &Path) -> Result<bool> {
+        let mut extractor = Extractor::new();
+        let mut files = BTreeMap::new();
+        let mut changed = false;
+
+        for entry in WalkBuilder::new(dir).build() {
+            let entry = entry?;
+
+            if !entry.file_type().map(|t| t.is_file()).unwrap_or(false) {
+                continue;
+            }
+
+            let filename = display_path(entry.path());
+            let lang = detect_language(&filename);
+
+            if lang == DetectedLanguage::Unknown {
+                continue;
+            }
+
+            let mtime = entry.metadata().ok().and_then(|m| m.modified().ok());
+
+            if let Some(existing) = self.files.remove(&filename) {
+                if mtime.is_some() && existing.mtime == mtime {
+                    files.insert(filename, existing);
+                    continue;
+                }
+            }
+
+            // Binary files have no definitions, but are still recorded so they aren't re-read
+            let symbols = match String::from_utf8(fs::read(entry.path())?) {
+                Ok(content) => extractor.extract(&filename, &content, &lang),
+                Err(_) => vec![],
+            };
+
+            files.insert(filename, FileSymbols { mtime, symbols });
+            changed = true;
+        }
+
+        // Anything left over has been deleted since we last looked
+        changed |= !self.files.is_empty();
+        self.files = files;
+
+        Ok(changed)
+    }
+
+    pub fn symbol_count(&self) -> usize {
+        self.files.values().map(|f| f.symbols.len()).sum()
+    }
+}
+
+/// Searches for definitions can be answered by the index alone: any line defining the term must
+/// be one we've extracted a definition from, so only those lines need matching against the
+/// pattern. The index should be refreshed before each search so that it's current. Output mimics
+/// the other backends so the hits are parsed as usual.
+impl SearchBackend for SymbolIndex {
+    fn dialect(&self) -> Dialect {
+        Dialect::Rust
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> backend::Result<String> {
+        let types = file_types(langs)?;
+        let files = self.files.iter().filter(|(filename, _)| match &types {
+            Some(t) => t.matched(filename, false).is_whitelist(),
+            None => true,
+        });
+
+        if filenames {
+            let listing: String = files.map(|(filename, _)| format!("{filename}\0")).collect();
+            return filter_filenames(&listing, pattern);
+        }
+
+        let re = Regex::new(pattern)?;
+        let mut output = String::new();
+
+        for (filename, file) in files {
+            let mut last_line = None;
+
+            // Several definitions may share a line, but the line should only be matched once
+            for symbol in &file.symbols {
+                if last_line == Some(symbol.line) {
+                    continue;
+                }
+                last_line = Some(symbol.line);
+
+                if let Some(m) = re.find(&symbol.text) {
+                    output.push_str(&format!(
+                        "{}\0{}:{}:{}\n",
+                        filename,
+                        symbol.line,
+                        m.start() + 1,
+                        symbol.text
+                    ));
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,150 @@
+use super::*;
+
+use std::env;
+
+use crate::backend::Memory;
+use crate::search::Search;
+
+/// An index of the fixtures, built from the files on disk
+fn fixtures_index() -> SymbolIndex {
+    let mut index = SymbolIndex::new(Path::new("/project"));
+    index.refresh(Path::new("test/fixtures")).unwrap();
+    index
+}
+
+#[test]
+fn project_key_stable() {
+    let key = project_key(Path::new("/home/me/project"));
+
+    assert_eq!(key, project_key(Path::new("/home/me/project")));
+    assert_ne!(key, project_key(Path::new("/home/me/project2")));
+    assert_eq!(key.len(), 16);
+}
+
+#[test]
+fn extract_definitions() {
+    let index = fixtures_index();
+    let cli = &index.files["test/fixtures/python/cli.py"];
+    let expected = Symbol {
+        name: "Cli".to_string(),
+        kind: SymbolKind::Class,
+        file: "test/fixtures/python/cli.py".to_string(),
+        line: 17,
+        col: 7,
+        lang: DetectedLanguage::Python,
+        text: "class Cli:".to_string(),
+    };
+
+    assert!(cli.symbols.contains(&expected));
+    assert!(cli.mtime.is_some());
+    assert!(!index
+        .files
+        .contains_key("test/fixtures/import-index/valid.json"));
+}
+
+#[test]
+/// Searches answered from the index should find exactly what searching the files would
+fn search_matches_files() {
+    let index = fixtures_index();
+    let cases = [
+        (SearchMode::Class, Language::Python, "Cli"),
+        (SearchMode::Class, Language::Scala, "Update"),
+        (SearchMode::Function, Language::Rust, "get_all_games"),
+        (SearchMode::Smart, Language::Go, "NewCache"),
+        (SearchMode::Smart, Language::Auto, "S3Client"),
+    ];
+
+    for (mode, lang, term) in cases {
+        let expected = Search::new(Box::new(Memory::fixtures()), &mode, &lang)
+            .search(term)
+            .unwrap();
+        let actual = Search::new(Box::new(index.clone()), &mode, &lang)
+            .search(term)
+            .unwrap();
+
+        assert!(!expected.is_empty(), "{mode:?} {term}");
+        assert_eq!(actual, expected, "{mode:?} {lang:?} {term}");
+    }
+}
+
+#[test]
+/// Only files modified since they were indexed should be read again, and deleted files dropped
+fn refresh_changed_files() {
+    let mut index = fixtures_index();
+    assert!(!index.refresh(Path::new("test/fixtures")).unwrap());
+
+    // Doctor the index so we can tell which files have been re-read
+    let stale = "test/fixtures/python/cli.py".to_string();
+    let fresh = "test/fixtures/rust/steam.rs".to_string();
+    let deleted = "test/fixtures/python/deleted.py".to_string();
+
+    index.files.get_mut(&stale).unwrap().mtime = None;
+    index.files.get_mut(&stale).unwrap().symbols.clear();
+    index.files.get_mut(&fresh).unwrap().symbols[0].name = "doctored".to_string();
+    index.files.insert(
+        deleted.clone(),
+        FileSymbols {
+            mtime: None,
+            symbols: vec![],
+        },
+    );
+
+    assert!(index.refresh(Path::new("test/fixtures")).unwrap());
+    assert!(!index.files[&stale].symbols.is_empty());
+    assert_eq!(index.files[&fresh].symbols[0].name, "doctored");
+    assert!(!index.files.contains_key(&deleted));
+}
+
+#[test]
+/// Definitions added to indexed files, or in new files, should be found once the index is
+/// refreshed, and those removed along with their files should not
+fn search_after_edits() {
+    use std::time::{Duration, SystemTime};
+
+    let dir = env::temp_dir().join(format!("qf-symbol-index-{}", std::process::id()));
+    let edited = dir.join("edited.py");
+    let added = dir.join("added.py");
+    let deleted = dir.join("deleted.py");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(&edited, "def first():\n    pass\n").unwrap();
+    fs::write(&deleted, "def deleted():\n    pass\n").unwrap();
+
+    // Backdate the file so that editing it is sure to change its mtime
+    let file = fs::File::options().write(true).open(&edited).unwrap();
+    file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(1)).unwrap();
+
+    let mut index = SymbolIndex::new(&dir);
+    index.refresh(&dir).unwrap();
+
+    fs::write(&edited, "def first():\n    pass\n\ndef second():\n    pass\n").unwrap();
+    fs::write(&added, "def third():\n    pass\n").unwrap();
+    fs::remove_file(&deleted).unwrap();
+
+    let symbols = SymbolCache::new(Some(index));
+    let found = |term: &str| {
+        let backend = symbols.backend(&SearchMode::Function).unwrap();
+        let search = Search::new(Box::new(backend), &SearchMode::Function, &Language::Python);
+        let hits = search.search(term).unwrap();
+        hits.into_iter().map(|h| (h.filename, h.line)).collect::<Vec<_>>()
+    };
+
+    assert!(symbols.index.as_ref().unwrap().borrow_mut().refresh(&dir).unwrap());
+    let (second, third, deleted) = (found("second"), found("third"), found("deleted"));
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(second, vec![(display_path(&edited), Some(4))]);
+    assert_eq!(third, vec![(display_path(&added), Some(1))]);
+    assert_eq!(deleted, vec![]);
+}
+
+#[test]
+/// A long-running process should only search the index in the modes it can answer
+fn symbol_cache_modes() {
+    let symbols = SymbolCache::new(Some(SymbolIndex::new(Path::new("/project"))));
+
+    assert!(symbols.backend(&SearchMode::Class).is_some());
+    assert!(symbols.backend(&SearchMode::Smart).is_some());
+    assert!(symbols.backend(&SearchMode::Import).is_none());
+    assert!(symbols.backend(&SearchMode::AllUsage).is_none());
+    assert!(SymbolCache::default().backend(&SearchMode::Class).is_none());
+}
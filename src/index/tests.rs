@@ -2,23 +2,78 @@ use super::*;
 
 use std::collections::HashMap;
 
-use crate::index::models::ImportIndex;
+use crate::index::models::{ImportCandidate, ImportIndex, IMPORT_INDEX_VERSION};
 
 #[test]
 fn read_import_index_valid() {
     let f = "test/fixtures/import-index/valid.json".to_string();
     let actual = get_import_index("rust", &Some(f)).unwrap();
     let expected = ImportIndex {
+        version: IMPORT_INDEX_VERSION,
         lang: "rust".to_string(),
         entries: HashMap::from([
-            ("PathBuf".to_string(), "use std::path::PathBuf".to_string()),
-            ("fs".to_string(), "use std::fs".to_string()),
+            (
+                "PathBuf".to_string(),
+                vec![ImportCandidate {
+                    import: "use std::path::PathBuf".to_string(),
+                    count: 1,
+                }],
+            ),
+            (
+                "fs".to_string(),
+                vec![ImportCandidate {
+                    import: "use std::fs".to_string(),
+                    count: 1,
+                }],
+            ),
         ]),
     };
 
     assert_eq!(actual, expected);
 }
 
+#[test]
+/// Candidates should be ranked by frequency whatever order they're stored in
+fn read_import_index_ranked() {
+    let f = "test/fixtures/import-index/valid-v2.json".to_string();
+    let actual = get_import_index("rust", &Some(f)).unwrap();
+
+    assert_eq!(
+        actual.imports("Error"),
+        vec!["use thiserror::Error;", "use std::io::Error;"]
+    );
+    assert_eq!(actual.imports("fs"), vec!["use std::fs;"]);
+    assert_eq!(actual.imports("PathBuf"), Vec::<String>::new());
+}
+
+#[test]
+fn read_import_index_unsupported_version() {
+    for f in ["future-version.json", "mixed-version.json"] {
+        let f = format!("test/fixtures/import-index/{f}");
+        let actual = get_import_index("rust", &Some(f)).unwrap_err();
+
+        assert!(matches!(actual, IndexError::Malformed { .. }))
+    }
+}
+
+#[test]
+fn import_index_add_ranks() {
+    let mut index = ImportIndex::new("rust");
+    index.add("Error", "use std::io::Error;", 1);
+    index.add("Error", "use thiserror::Error;", 1);
+    index.add("Error", "use thiserror::Error;", 1);
+    index.add("Error", "use anyhow::Error;", 1);
+
+    assert_eq!(
+        index.imports("Error"),
+        vec![
+            "use thiserror::Error;",
+            "use anyhow::Error;",
+            "use std::io::Error;"
+        ]
+    );
+}
+
 #[test]
 fn read_import_index_bad_file() {
     let f = "test/fixtures/import-index/no-such-file.json".to_string();
@@ -0,0 +1,457 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, ProtocolError, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+};
+use lsp_types::request::{
+    CodeActionRequest, GotoDefinition, References, Request as _, WorkspaceSymbolRequest,
+};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, CodeActionResponse, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, GotoDefinitionParams,
+    GotoDefinitionResponse, Location, OneOf, Position, Range, ReferenceParams, ServerCapabilities,
+    SymbolInformation, SymbolKind, TextDocumentPositionParams, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Url, WorkspaceEdit, WorkspaceSymbolParams,
+    WorkspaceSymbolResponse,
+};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::args::{Args, IndexLayer, Language, OutputStyle, SearchMode};
+use crate::backend::SearchBackend;
+use crate::fmt::{rank_imports, HitFormatter};
+use crate::index::symbols::SymbolCache;
+use crate::index::IndexCache;
+use crate::project::Project;
+use crate::search::statement::find_statement;
+use crate::search::{detect_language, DetectedLanguage, Hit, Search, SearchError};
+
+#[derive(Error, Debug)]
+pub enum LspError {
+    #[error("LSP protocol error: {0}")]
+    Protocol(#[from] ProtocolError),
+    #[error("IO error talking to the client: {0}")]
+    IO(#[from] io::Error),
+    #[error("Lost connection to the client")]
+    Disconnected,
+    #[error("Malformed message from the client: {0}")]
+    Malformed(#[from] serde_json::Error),
+    #[error("Search failed: {0}")]
+    Search(#[from] SearchError),
+}
+
+type Result<T> = std::result::Result<T, LspError>;
+
+/// The features we offer, announced to the client when it connects
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..Default::default()
+    }
+}
+
+/// Convert a position within a line, counted in UTF-16 code units as LSP does, to a byte offset
+fn byte_offset(line: &str, character: u32) -> usize {
+    let mut units = 0;
+
+    for (i, c) in line.char_indices() {
+        if units >= character as usize {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+
+    line.len()
+}
+
+/// Count the UTF-16 code units in a string, to express a byte offset as an LSP position
+fn utf16_len(s: &str) -> u32 {
+    s.encode_utf16().count() as u32
+}
+
+/// Find the identifier under the cursor, if there is one
+fn word_at(text: &str, pos: Position) -> Option<String> {
+    let line = text.lines().nth(pos.line as usize)?;
+    let offset = byte_offset(line, pos.character);
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    let start = line[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word(*c))
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(offset);
+    let end = line[offset..]
+        .char_indices()
+        .find(|(_, c)| !is_word(*c))
+        .map(|(i, _)| offset + i)
+        .unwrap_or(line.len());
+
+    if start == end {
+        None
+    } else {
+        Some(line[start..end].to_string())
+    }
+}
+
+/// Pick the line a new import should go on: just after the last existing import (or the end of
+/// it, if it's split over several lines), or at the top of the file if there are none. Only
+/// top-level imports count, not those indented inside a module or function.
+fn import_line(text: &str, lang: &DetectedLanguage) -> u32 {
+    let r =
+        Regex::new(r"^(?:import|from|use|package|#\s*include|require(?:_relative)?)\b").unwrap();
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, l)| r.is_match(l))
+        .last()
+        .map(|(i, _)| find_statement(text, i, lang).map(|s| s.end).unwrap_or(i) as u32 + 1)
+        .unwrap_or(0)
+}
+
+/// Answers LSP requests for the lifetime of an editor session, mapping definitions, references,
+/// workspace symbols and import code actions onto the regular search modes
+pub struct LspServer {
+    backend: Rc<dyn SearchBackend>,
+    lang: Option<Language>,
+    project: Project,
+    use_import_index_file: bool,
+    indexes: IndexCache,
+    symbols: SymbolCache,
+    documents: HashMap<Url, String>,
+    root: PathBuf,
+}
+
+impl LspServer {
+    pub fn new(backend: Box<dyn SearchBackend>, args: &Args) -> LspServer {
+        LspServer {
+            backend: Rc::from(backend),
+            lang: args.lang.clone(),
+            project: Project::default(),
+            use_import_index_file: args.use_import_index_file,
+            indexes: IndexCache::new(
+                &args.import_index_file,
+                &args.import_index_layers,
+                &args.no_bundled_index,
+            ),
+            symbols: SymbolCache::default(),
+            documents: HashMap::new(),
+            root: env::current_dir().unwrap_or_default(),
+        }
+    }
+
+    /// Serve the given project, whose language is used for documents we don't recognise
+    pub fn with_project(mut self, project: Project) -> LspServer {
+        self.project = project;
+        self
+    }
+
+    /// Answer definition searches from the project's symbol index
+    pub fn with_symbols(mut self, symbols: SymbolCache) -> LspServer {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Complete the initialisation handshake with the client and serve it until it shuts us down
+    pub fn start(&mut self, connection: &Connection) -> Result<()> {
+        connection.initialize(serde_json::to_value(capabilities())?)?;
+        self.run(connection)
+    }
+
+    /// Handle messages until the client asks us to shut down or goes away
+    pub fn run(&mut self, connection: &Connection) -> Result<()> {
+        for msg in &connection.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+
+                    let response = self.handle_request(req);
+                    connection
+                        .sender
+                        .send(Message::Response(response))
+                        .map_err(|_| LspError::Disconnected)?;
+                }
+                Message::Notification(n) => self.handle_notification(n),
+                Message::Response(_) => (),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&mut self, req: Request) -> Response {
+        match req.method.as_str() {
+            GotoDefinition::METHOD => self.dispatch(req, Self::definition),
+            References::METHOD => self.dispatch(req, Self::references),
+            WorkspaceSymbolRequest::METHOD => self.dispatch(req, Self::symbols),
+            CodeActionRequest::METHOD => self.dispatch(req, Self::code_actions),
+            m => Response::new_err(
+                req.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unknown method: {m}"),
+            ),
+        }
+    }
+
+    /// Decode the parameters of a request, run its handler and encode the outcome as a response
+    fn dispatch<P, R>(&mut self, req: Request, handler: fn(&mut Self, P) -> Result<R>) -> Response
+    where
+        P: DeserializeOwned,
+        R: Serialize,
+    {
+        let params = match serde_json::from_value(req.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return Response::new_err(req.id, ErrorCode::InvalidParams as i32, e.to_string())
+            }
+        };
+
+        match handler(self, params) {
+            Ok(res) => Response::new_ok(req.id, res),
+            Err(e) => Response::new_err(req.id, ErrorCode::RequestFailed as i32, e.to_string()),
+        }
+    }
+
+    /// Keep track of the content of open documents, which may differ from what's on disk
+    fn handle_notification(&mut self, n: Notification) {
+        match n.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                if let Ok(p) = serde_json::from_value::<DidOpenTextDocumentParams>(n.params) {
+                    self.documents
+                        .insert(p.text_document.uri, p.text_document.text);
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                if let Ok(p) = serde_json::from_value::<DidChangeTextDocumentParams>(n.params) {
+                    // We ask for full sync, so the last change holds the whole document
+                    if let Some(change) = p.content_changes.into_iter().last() {
+                        self.documents.insert(p.text_document.uri, change.text);
+                    }
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                if let Ok(p) = serde_json::from_value::<DidCloseTextDocumentParams>(n.params) {
+                    self.documents.remove(&p.text_document.uri);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// The content of a document, as last sent by the client or otherwise read from disk
+    fn text(&self, uri: &Url) -> Option<String> {
+        if let Some(text) = self.documents.get(uri) {
+            return Some(text.clone());
+        }
+
+        fs::read_to_string(uri.to_file_path().ok()?).ok()
+    }
+
+    /// The language to search in on behalf of a document, falling back to the language hint the
+    /// server was started with (or failing that the project's) if we don't recognise the file
+    fn lang_of(&self, uri: &Url) -> Language {
+        match detect_language(uri.path()).language() {
+            Language::Auto => self.project.language(self.lang.as_ref()),
+            lang => lang,
+        }
+    }
+
+    /// The identifier at a position in a document, along with the language to look for it in
+    fn term_at(&self, pos: &TextDocumentPositionParams) -> Option<(String, Language)> {
+        let text = self.text(&pos.text_document.uri)?;
+        let term = word_at(&text, pos.position)?;
+
+        Some((term, self.lang_of(&pos.text_document.uri)))
+    }
+
+    fn search(&self, mode: &SearchMode, lang: &Language, term: &str) -> Result<Vec<Hit>> {
+        if let Err(e) = self.symbols.refresh(mode) {
+            eprintln!("Failed to refresh symbol index: {e}");
+        }
+
+        let backend = self
+            .symbols
+            .backend(mode)
+            .unwrap_or_else(|| self.backend.clone());
+        let languages = match lang {
+            Language::Auto => self.project.search_languages(mode, self.lang.as_ref()),
+            lang => vec![lang.clone()],
+        };
+        let search = Search::new(Box::new(backend), mode, lang)
+            .with_project(self.project.clone())
+            .with_languages(languages);
+        Ok(search.search(term)?)
+    }
+
+    /// Express a hit as a location, covering the term itself within the matching line
+    fn location(&self, h: &Hit) -> Option<Location> {
+        let line = (h.line? as u32).checked_sub(1)?;
+        let col = (h.col? as usize).checked_sub(1)?;
+
+        let (start, end) = match h.text.get(col..).and_then(|rest| rest.find(&h.term)) {
+            Some(i) => (col + i, col + i + h.term.len()),
+            None if h.text.is_char_boundary(col) => (col, col),
+            None => (0, 0),
+        };
+
+        Some(Location {
+            uri: Url::from_file_path(self.root.join(&h.filename)).ok()?,
+            range: Range::new(
+                Position::new(line, utf16_len(&h.text[..start])),
+                Position::new(line, utf16_len(&h.text[..end])),
+            ),
+        })
+    }
+
+    /// Look for a class or function definition, trying the smart search first and then each kind
+    /// in turn in case the naming conventions don't hold
+    fn definition(
+        &mut self,
+        params: GotoDefinitionParams,
+    ) -> Result<Option<GotoDefinitionResponse>> {
+        let (term, lang) = match self.term_at(&params.text_document_position_params) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+
+        for mode in [SearchMode::Smart, SearchMode::Class, SearchMode::Function] {
+            let hits = self.search(&mode, &lang, &term)?;
+
+            if !hits.is_empty() {
+                let locations = hits.iter().filter_map(|h| self.location(h)).collect();
+                return Ok(Some(GotoDefinitionResponse::Array(locations)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn references(&mut self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let (term, lang) = match self.term_at(&params.text_document_position) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+
+        let hits = self.search(&SearchMode::AllUsage, &lang, &term)?;
+        Ok(Some(hits.iter().filter_map(|h| self.location(h)).collect()))
+    }
+
+    /// Find classes and functions with exactly the name queried
+    #[allow(deprecated)]
+    fn symbols(
+        &mut self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<WorkspaceSymbolResponse>> {
+        let mut symbols = vec![];
+        let lang = self.project.language(self.lang.as_ref());
+
+        if params.query.is_empty() {
+            return Ok(Some(WorkspaceSymbolResponse::Flat(symbols)));
+        }
+
+        for (mode, kind) in [
+            (SearchMode::Class, SymbolKind::CLASS),
+            (SearchMode::Function, SymbolKind::FUNCTION),
+        ] {
+            for h in self.search(&mode, &lang, &params.query)? {
+                if let Some(location) = self.location(&h) {
+                    symbols.push(SymbolInformation {
+                        name: params.query.clone(),
+                        kind,
+                        tags: None,
+                        deprecated: None,
+                        location,
+                        container_name: None,
+                    });
+                }
+            }
+        }
+
+        Ok(Some(WorkspaceSymbolResponse::Flat(symbols)))
+    }
+
+    /// Offer to import the identifier at the start of the range, using imports of it found
+    /// elsewhere in the project, most common first, or else the import index
+    fn code_actions(&mut self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let uri = params.text_document.uri;
+        let text = match self.text(&uri) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        let term = match word_at(&text, params.range.start) {
+            Some(t) => t,
+            None => return Ok(None),
+        };
+        let lang = self.lang_of(&uri);
+
+        let target = uri.to_file_path().ok();
+        let formatter = HitFormatter::new(&OutputStyle::Import, &SearchMode::Import)
+            .with_target(target.as_deref());
+        let hits = self.search(&SearchMode::Import, &lang, &term)?;
+        let mut imports: Vec<(String, Option<IndexLayer>)> =
+            rank_imports(hits.iter().filter_map(|h| formatter.write(h).ok()))
+                .into_iter()
+                .map(|r| (r.import, None))
+                .collect();
+
+        // Editors ask for code actions whenever the cursor moves, so a missing or broken index
+        // just means nothing to offer rather than an error every time
+        if imports.is_empty() && self.use_import_index_file {
+            for lang in self.project.index_languages(&lang) {
+                let lang_str = format!("{:?}", lang).to_lowercase();
+                imports.extend(
+                    self.indexes
+                        .lookup(&lang_str, &term)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|i| (i.import, Some(i.layer))),
+                );
+            }
+        }
+
+        imports.retain(|(import, _)| !text.lines().any(|l| l.trim() == import));
+
+        let pos = Position::new(import_line(&text, &detect_language(uri.path())), 0);
+        let actions = imports
+            .into_iter()
+            .map(|(import, layer)| {
+                let edit = TextEdit::new(Range::new(pos, pos), format!("{import}\n"));
+                let title = match layer {
+                    Some(l) => format!("Add {import} (from the {l} import index)"),
+                    None => format!("Add {import}"),
+                };
+
+                CodeActionOrCommand::CodeAction(CodeAction {
+                    title,
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Ok(Some(actions))
+    }
+}
@@ -0,0 +1,364 @@
+use super::*;
+
+use clap::Parser;
+use lsp_server::RequestId;
+use lsp_types::{
+    PartialResultParams, ReferenceContext, TextDocumentIdentifier, TextDocumentItem,
+    WorkDoneProgressParams,
+};
+use serde_json::json;
+
+use crate::backend::Memory;
+
+/// A language server over the in-memory fixtures, started with the given command line options
+fn server(opts: &[&str]) -> LspServer {
+    let args = Args::parse_from([&["qf"], opts, &["lsp"]].concat());
+    LspServer::new(Box::new(Memory::fixtures()), &args)
+}
+
+/// Open a document in the server with the given content, returning its uri
+fn open(server: &mut LspServer, filename: &str, text: &str) -> Url {
+    let uri = Url::from_file_path(server.root.join(filename)).unwrap();
+    let params = DidOpenTextDocumentParams {
+        text_document: TextDocumentItem::new(uri.clone(), "".to_string(), 1, text.to_string()),
+    };
+
+    server.handle_notification(Notification::new(
+        DidOpenTextDocument::METHOD.to_string(),
+        params,
+    ));
+    uri
+}
+
+fn position(uri: &Url, line: u32, character: u32) -> TextDocumentPositionParams {
+    TextDocumentPositionParams::new(
+        TextDocumentIdentifier::new(uri.clone()),
+        Position::new(line, character),
+    )
+}
+
+fn location(server: &LspServer, filename: &str, line: u32, start: u32, end: u32) -> Location {
+    Location::new(
+        Url::from_file_path(server.root.join(filename)).unwrap(),
+        Range::new(Position::new(line, start), Position::new(line, end)),
+    )
+}
+
+#[test]
+fn word_at_cursor() {
+    let text = "let x = Foo::bar(y);\nconst café_au_lait = 1;";
+
+    assert_eq!(word_at(text, Position::new(0, 9)), Some("Foo".to_string()));
+    assert_eq!(word_at(text, Position::new(0, 8)), Some("Foo".to_string()));
+    assert_eq!(word_at(text, Position::new(0, 11)), Some("Foo".to_string()));
+    assert_eq!(word_at(text, Position::new(0, 14)), Some("bar".to_string()));
+    assert_eq!(word_at(text, Position::new(0, 7)), None);
+    assert_eq!(
+        word_at(text, Position::new(1, 13)),
+        Some("café_au_lait".to_string())
+    );
+    assert_eq!(word_at(text, Position::new(2, 0)), None);
+}
+
+#[test]
+fn location_without_position() {
+    let server = server(&[]);
+    let hit = |line, col| Hit {
+        term: "Foo".to_string(),
+        filename: "foo.rs".to_string(),
+        line,
+        col,
+        text: "struct Foo;".to_string(),
+        lang: DetectedLanguage::Rust,
+    };
+
+    assert!(server.location(&hit(Some(1), Some(8))).is_some());
+    assert_eq!(server.location(&hit(Some(0), Some(8))), None);
+    assert_eq!(server.location(&hit(Some(1), Some(0))), None);
+    assert_eq!(server.location(&hit(None, None)), None);
+}
+
+#[test]
+fn import_line_after_imports() {
+    let cases = [
+        ("import os\nimport sys\n\nx = 1\n", DetectedLanguage::Python, 2),
+        ("package foo\n\nfunc main() {}\n", DetectedLanguage::Go, 1),
+        ("#include <stdio.h>\n#include \"app.h\"\n\nint x;\n", DetectedLanguage::C, 2),
+        ("require \"csv\"\n\nclass Export\nend\n", DetectedLanguage::Ruby, 1),
+        ("x = 1\n", DetectedLanguage::Python, 0),
+        (
+            "import os\n\ndef main():\n    import sys\n    return sys.argv\n",
+            DetectedLanguage::Python,
+            1,
+        ),
+        (
+            "use std::io;\n\nfn main() {}\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n}\n",
+            DetectedLanguage::Rust,
+            1,
+        ),
+    ];
+
+    for (text, lang, expected) in cases {
+        assert_eq!(import_line(text, &lang), expected, "{text}");
+    }
+}
+
+#[test]
+/// New imports should go after a statement split over several lines, not inside it
+fn import_line_after_multiline_imports() {
+    let cases = [
+        (
+            "package main\n\nimport (\n\t\"fmt\"\n\t\"os\"\n)\n\nfunc main() {}\n",
+            DetectedLanguage::Go,
+            6,
+        ),
+        (
+            "from os.path import (\n    join,\n    split,\n)\n\nx = 1\n",
+            DetectedLanguage::Python,
+            4,
+        ),
+        (
+            concat!(
+                "use std::io;\nuse std::collections::{\n    HashMap,\n    HashSet,\n};\n",
+                "\nfn main() {}\n"
+            ),
+            DetectedLanguage::Rust,
+            5,
+        ),
+    ];
+
+    for (text, lang, expected) in cases {
+        assert_eq!(import_line(text, &lang), expected, "{text}");
+    }
+}
+
+#[test]
+fn definition_of_class() {
+    let mut server = server(&[]);
+    let uri = open(&mut server, "main.py", "cli = Cli()\n");
+    let params = GotoDefinitionParams {
+        text_document_position_params: position(&uri, 0, 7),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let actual = server.definition(params).unwrap();
+    let expected = Some(GotoDefinitionResponse::Array(vec![location(
+        &server,
+        "test/fixtures/python/cli.py",
+        16,
+        6,
+        9,
+    )]));
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn definition_of_function() {
+    let mut server = server(&[]);
+    let uri = open(&mut server, "main.rs", "let games = get_all_games();\n");
+    let params = GotoDefinitionParams {
+        text_document_position_params: position(&uri, 0, 15),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let actual = server.definition(params).unwrap();
+    let expected = Some(GotoDefinitionResponse::Array(vec![
+        location(&server, "test/fixtures/rust/steam.rs", 28, 7, 20),
+        location(&server, "test/fixtures/rust/steam.rs", 84, 7, 20),
+    ]));
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn references_to_term() {
+    let mut server = server(&[]);
+    let uri = open(&mut server, "main.py", "client = S3Client()\n");
+    let params = ReferenceParams {
+        text_document_position: position(&uri, 0, 12),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+        context: ReferenceContext {
+            include_declaration: true,
+        },
+    };
+
+    let actual = server.references(params).unwrap().unwrap();
+
+    assert!(actual.len() > 1);
+    assert!(actual.contains(&location(
+        &server,
+        "test/fixtures/python/client.py",
+        11,
+        6,
+        14
+    )));
+}
+
+#[test]
+fn workspace_symbols() {
+    let mut server = server(&["--lang", "python"]);
+    let params = WorkspaceSymbolParams {
+        query: "Cli".to_string(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    let actual = match server.symbols(params).unwrap() {
+        Some(WorkspaceSymbolResponse::Flat(symbols)) => symbols,
+        r => panic!("Unexpected response: {r:?}"),
+    };
+
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].kind, SymbolKind::CLASS);
+    assert_eq!(
+        actual[0].location,
+        location(&server, "test/fixtures/python/cli.py", 16, 6, 9)
+    );
+}
+
+/// Request code actions at a position, returning the text and position of each edit offered
+fn code_actions(
+    server: &mut LspServer,
+    uri: &Url,
+    line: u32,
+    character: u32,
+) -> Vec<(String, Position)> {
+    let pos = Position::new(line, character);
+    let params = CodeActionParams {
+        text_document: TextDocumentIdentifier::new(uri.clone()),
+        range: Range::new(pos, pos),
+        context: Default::default(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+
+    server
+        .code_actions(params)
+        .unwrap()
+        .unwrap()
+        .into_iter()
+        .map(|action| match action {
+            CodeActionOrCommand::CodeAction(a) => {
+                let edit = &a.edit.unwrap().changes.unwrap()[uri][0];
+                (edit.new_text.clone(), edit.range.start)
+            }
+            c => panic!("Unexpected command: {c:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn code_action_import() {
+    let mut server = server(&["--no-import-index-file"]);
+    let uri = open(
+        &mut server,
+        "main.rs",
+        "use std::fs;\n\nfn main() {\n    let m = HashMap::new();\n}\n",
+    );
+
+    assert_eq!(
+        code_actions(&mut server, &uri, 3, 13),
+        vec![(
+            "use std::collections::HashMap;\n".to_string(),
+            Position::new(1, 0)
+        )]
+    );
+}
+
+#[test]
+fn code_action_import_index_fallback() {
+    let mut server = server(&[
+        "--import-index-file",
+        "test/fixtures/import-index/valid.json",
+    ]);
+    let uri = open(
+        &mut server,
+        "main.rs",
+        "fn main() {\n    PathBuf::new();\n}\n",
+    );
+
+    assert_eq!(
+        code_actions(&mut server, &uri, 1, 6),
+        vec![("use std::path::PathBuf\n".to_string(), Position::new(0, 0))]
+    );
+    assert!(server.indexes.is_loaded("rust"));
+}
+
+#[test]
+/// The latest content sent by the client should be used, and nothing offered if already imported
+fn code_action_tracks_changes() {
+    let mut server = server(&["--no-import-index-file"]);
+    let uri = open(&mut server, "main.rs", "let m = HashMap::new();\n");
+    let change = json!({
+        "textDocument": {"uri": uri, "version": 2},
+        "contentChanges": [{"text": "use std::collections::HashMap;\nlet m = HashMap::new();\n"}],
+    });
+
+    server.handle_notification(Notification::new(
+        DidChangeTextDocument::METHOD.to_string(),
+        change,
+    ));
+
+    assert_eq!(code_actions(&mut server, &uri, 1, 9), vec![]);
+}
+
+#[test]
+/// Requests should be answered over the connection until the client shuts the server down
+fn run_until_shutdown() {
+    let mut server = server(&[]);
+    let (server_conn, client_conn) = Connection::memory();
+    let messages = [
+        Message::Request(Request::new(
+            RequestId::from(1),
+            WorkspaceSymbolRequest::METHOD.to_string(),
+            json!({"query": "S3Client"}),
+        )),
+        Message::Request(Request::new(
+            RequestId::from(2),
+            "qf/frobnicate".to_string(),
+            json!({}),
+        )),
+        Message::Request(Request::new(
+            RequestId::from(3),
+            "shutdown".to_string(),
+            json!(null),
+        )),
+        Message::Notification(Notification::new("exit".to_string(), json!(null))),
+    ];
+
+    for m in messages {
+        client_conn.sender.send(m).unwrap();
+    }
+
+    server.run(&server_conn).unwrap();
+
+    let responses: Vec<Response> = client_conn
+        .receiver
+        .try_iter()
+        .map(|m| match m {
+            Message::Response(r) => r,
+            m => panic!("Unexpected message: {m:?}"),
+        })
+        .collect();
+
+    assert_eq!(responses.len(), 3);
+    assert_eq!(
+        responses[0]
+            .result
+            .as_ref()
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        responses[1].error.as_ref().unwrap().code,
+        ErrorCode::MethodNotFound as i32
+    );
+    assert_eq!(responses[2].id, RequestId::from(3));
+}
@@ -1,32 +1,165 @@
-mod ag;
 mod args;
+mod backend;
 mod index;
 mod fmt;
+mod lsp;
+mod project;
 mod search;
+mod serve;
 mod sort;
 
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process;
 
 use clap::Parser;
+use lsp_server::Connection;
 
-use crate::ag::Ag;
-use crate::args::{Args, Language, OutputStyle, SearchMode};
-use crate::index::get_import_index;
+use crate::args::{Args, Command, IndexCommand, Language, OutputStyle, SearchMode};
+use crate::backend::{get_backend, get_server_backend, SearchBackend};
+use crate::index::layers::{language_layers, IndexPaths, LayeredImportIndex};
+use crate::index::imports::{build_import_index, write_import_index};
+use crate::index::models::SymbolIndex;
+use crate::index::symbols::{
+    get_symbol_index, indexes_mode, open_symbol_index, write_symbol_index, SymbolCache,
+};
 use crate::fmt::HitFormatter;
+use crate::lsp::LspServer;
+use crate::project::Project;
 use crate::search::Search;
+use crate::serve::Server;
 
-fn print_import_from_index(term: &str, lang: &Language, f: &Option<String>) {
-    let lang_str = format!("{:?}", lang).to_lowercase();
-    let index = get_import_index(&lang_str, &f).unwrap_or_else(|e| {
-        eprintln!("Unexpected error reading index: {e}");
+fn print_import_from_index(term: &str, langs: &[Language], args: &Args) {
+    for lang in langs {
+        let lang_str = format!("{:?}", lang).to_lowercase();
+        let layers = language_layers(&args.import_index_layers, &lang_str, &args.no_bundled_index);
+        let index = LayeredImportIndex::load(
+            &lang_str,
+            &args.import_index_file,
+            &layers,
+            &IndexPaths::from_env(),
+        )
+        .unwrap_or_else(|e| {
+            eprintln!("Unexpected error reading index: {e}");
+            process::exit(1);
+        });
+
+        for res in index.imports(term) {
+            if args.show_index_layer {
+                println!("{}\t{}", res.import, res.layer);
+            } else {
+                println!("{}", res.import);
+            }
+        }
+    }
+}
+
+/// The project we're searching in, which the language defaults to
+fn project() -> Project {
+    Project::detect(&env::current_dir().unwrap_or_default())
+}
+
+/// The project's symbol index, held open for a long-running process if it has one
+fn symbols(args: &Args) -> SymbolCache {
+    if !args.use_symbol_index {
+        return SymbolCache::default();
+    }
+
+    match open_symbol_index(&env::current_dir().unwrap_or_default()) {
+        Ok(index) => SymbolCache::new(index),
+        Err(e) => {
+            eprintln!("Ignoring symbol index: {e}");
+            SymbolCache::default()
+        }
+    }
+}
+
+fn serve(args: &Args) {
+    let mut server = Server::new(get_server_backend(&args.backend), args)
+        .with_project(project())
+        .with_symbols(symbols(args));
+
+    if let Err(e) = server.run(io::stdin().lock(), io::stdout().lock()) {
+        eprintln!("Server IO error: {e}");
         process::exit(1);
-    });
+    }
+}
+
+fn lsp(args: &Args) {
+    let (connection, io_threads) = Connection::stdio();
+    let mut server = LspServer::new(get_server_backend(&args.backend), args)
+        .with_project(project())
+        .with_symbols(symbols(args));
+
+    let res = server.start(&connection);
+    drop(connection);
 
-    for res in index.entries.get(term).into_iter() {
-        println!("{res}");
+    if let Err(e) = res.and_then(|_| Ok(io_threads.join()?)) {
+        eprintln!("Language server error: {e}");
+        process::exit(1);
     }
 }
 
+fn index_symbols() {
+    let root = env::current_dir().unwrap_or_default();
+    let mut index = get_symbol_index(&root)
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| SymbolIndex::new(&root));
+
+    let res = index
+        .refresh(Path::new("."))
+        .and_then(|_| write_symbol_index(&index));
+
+    match res {
+        Ok(path) => println!(
+            "Indexed {} symbols in {} files to {}",
+            index.symbol_count(),
+            index.files.len(),
+            path.display()
+        ),
+        Err(e) => {
+            eprintln!("Failed to index symbols: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+fn index_build(lang: &Language, output: &Option<String>, paths: &[PathBuf]) {
+    if *lang == Language::Auto {
+        eprintln!("A language must be given to build an import index");
+        process::exit(1);
+    }
+
+    let res = build_import_index(lang, paths)
+        .and_then(|index| Ok((index.entries.len(), write_import_index(&index, output)?)));
+
+    match res {
+        Ok((count, path)) => println!("Indexed {} imports to {}", count, path.display()),
+        Err(e) => {
+            eprintln!("Failed to build import index: {e}");
+            process::exit(1);
+        }
+    }
+}
+
+/// Definition searches can be answered from the project's symbol index if it has one; anything
+/// else goes to the search engine
+fn get_search_backend(args: &Args) -> Box<dyn SearchBackend> {
+    if args.use_symbol_index && indexes_mode(&args.mode) {
+        let root = env::current_dir().unwrap_or_default();
+
+        match open_symbol_index(&root) {
+            Ok(Some(index)) => return Box::new(index),
+            Ok(None) => (),
+            Err(e) => eprintln!("Ignoring symbol index: {e}"),
+        }
+    }
+
+    get_backend(&args.backend)
+}
+
 fn main() {
     let args = Args::parse();
 
@@ -35,26 +168,73 @@ fn main() {
         process::exit(1);
     }
 
-    let search = Search::new(Ag::default(), &args.mode, &args.lang);
-    let hits = search.search(&args.term).unwrap();
-    let formatter = HitFormatter::new(&args.output_style);
+    match args.command {
+        Some(Command::Serve) => return serve(&args),
+        Some(Command::Lsp) => return lsp(&args),
+        Some(Command::Index { command: IndexCommand::Symbols }) => return index_symbols(),
+        Some(Command::Index {
+            command: IndexCommand::Build { ref lang, ref output, ref paths },
+        }) => return index_build(lang, output, paths),
+        None => (),
+    }
+
+    let term = args.term.clone().unwrap_or_default();
+    let project = project();
+    let lang = project.language(args.lang.as_ref());
+    let search = Search::new(get_search_backend(&args), &args.mode, &lang)
+        .with_project(project.clone())
+        .with_languages(project.search_languages(&args.mode, args.lang.as_ref()));
+    let hits = search.search(&term).unwrap_or_else(|e| {
+        eprintln!("Search failed: {e}");
+        process::exit(1);
+    });
+    let formatter = HitFormatter::new(&args.output_style, &args.mode)
+        .with_target(args.target_file.as_deref());
 
-    for h in &hits {
-        println!("{}", formatter.write(h).unwrap());
+    let mut found = !hits.is_empty();
 
+    // Generated imports are ranked across all the hits, so the first is the most common one
+    if args.output_style == OutputStyle::Import {
+        let mut imports = formatter.write_imports(&hits).unwrap_or_else(|e| {
+            eprintln!("Failed to generate imports: {e}");
+            process::exit(1);
+        });
+        found = !imports.is_empty();
         if args.first_hit {
-            break;
+            imports.truncate(1);
+        }
+
+        for r in imports {
+            if args.show_counts {
+                println!("{}\t{}", r.import, r.count);
+            } else {
+                println!("{}", r.import);
+            }
+        }
+    } else {
+        let shown = if args.first_hit {
+            &hits[..hits.len().min(1)]
+        } else {
+            &hits[..]
+        };
+
+        let lines = formatter.write_all(shown).unwrap_or_else(|e| {
+            eprintln!("Failed to format hits: {e}");
+            process::exit(1);
+        });
+
+        for line in lines {
+            println!("{line}");
         }
     }
 
     // For generating imports specifically, we can make supplemental use of the imports index;
-    // we'll only do this if we didn't find any hits in the local project
-    if hits.len() == 0 &&
+    // we'll only do this if we didn't find anything to import in the local project
+    if !found &&
         args.use_import_index_file &&
         args.mode == SearchMode::Import &&
         args.output_style == OutputStyle::Import {
 
-        // TODO: Also accept an override for the index file and pass it into the function
-        print_import_from_index(&args.term, &args.lang, &args.import_index_file)
+        print_import_from_index(&term, &project.index_languages(&lang), &args)
     }
 }
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests;
+
+use std::path::Path;
+
+use crate::args::{Language, SearchMode};
+
+/// Files found at the root of a project which give away the languages it's written in. sbt builds
+/// java sources alongside scala ones, and a gradle build script (in groovy or kotlin) says nothing
+/// about the language of the project it builds, so either language may be found in their projects.
+const MARKERS: [(&str, &[Language]); 11] = [
+    ("Cargo.toml", &[Language::Rust]),
+    ("Gemfile", &[Language::Ruby]),
+    ("build.gradle", &[Language::Java, Language::Kotlin]),
+    ("build.gradle.kts", &[Language::Java, Language::Kotlin]),
+    ("build.sbt", &[Language::Scala, Language::Java]),
+    ("go.mod", &[Language::Go]),
+    ("package.json", &[Language::Js]),
+    ("pom.xml", &[Language::Java]),
+    ("pyproject.toml", &[Language::Python]),
+    ("setup.py", &[Language::Python]),
+    ("tsconfig.json", &[Language::Ts]),
+];
+
+/// What we know about the project being searched, used to fill in for a language hint when none
+/// is given
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Project {
+    pub languages: Vec<Language>,
+}
+
+impl Project {
+    /// Find the languages of the project a directory is in from the marker files in the nearest
+    /// directory, at or above it, which has any. A typescript project has a package.json too, but
+    /// is taken to be written in typescript alone.
+    pub fn detect(dir: &Path) -> Project {
+        for d in dir.ancestors() {
+            let mut languages: Vec<Language> = vec![];
+
+            for (file, langs) in MARKERS {
+                if !d.join(file).is_file() {
+                    continue;
+                }
+
+                for lang in langs {
+                    if !languages.contains(lang) {
+                        languages.push(lang.clone());
+                    }
+                }
+            }
+
+            if languages.contains(&Language::Ts) {
+                languages.retain(|l| *l != Language::Js);
+            }
+
+            if !languages.is_empty() {
+                return Project { languages };
+            }
+        }
+
+        Project::default()
+    }
+
+    /// The language to search in: the one given, if any (auto included), otherwise the project's
+    /// if it's written in just one language. Projects mixing several are left to auto mode.
+    pub fn language(&self, lang: Option<&Language>) -> Language {
+        match (lang, self.languages.as_slice()) {
+            (Some(lang), _) => lang.clone(),
+            (None, [only]) => only.clone(),
+            (None, _) => Language::Auto,
+        }
+    }
+
+    /// The languages whose files are searched for the given language: just that one (auto
+    /// meaning all of them) if one is given, otherwise those of the project. Files and usages
+    /// aren't particular to any language, so they're searched for everywhere unless asked.
+    pub fn search_languages(&self, mode: &SearchMode, lang: Option<&Language>) -> Vec<Language> {
+        match (lang, mode) {
+            (Some(lang), _) => vec![lang.clone()],
+            (None, SearchMode::File | SearchMode::AllUsage) => vec![],
+            (None, _) => self.languages.clone(),
+        }
+    }
+
+    /// The languages whose import indexes can be used for a search in the given language: all of
+    /// the project's if it's auto
+    pub fn index_languages(&self, lang: &Language) -> Vec<Language> {
+        match lang {
+            Language::Auto => self.languages.clone(),
+            lang => vec![lang.clone()],
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use super::*;
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn fixture(dir: &str) -> PathBuf {
+    PathBuf::from("test/fixtures/projects").join(dir)
+}
+
+#[test]
+/// Markers should be found in any directory above the one we're in
+fn detect_from_subdirectory() {
+    let actual = Project::detect(&fixture("rust/src/bin"));
+
+    assert_eq!(actual.languages, vec![Language::Rust]);
+}
+
+#[test]
+/// Only the nearest directory with markers counts, but every marker in it does
+fn detect_nearest_project() {
+    assert_eq!(
+        Project::detect(&fixture("polyglot")).languages,
+        vec![Language::Go, Language::Python]
+    );
+    assert_eq!(
+        Project::detect(&fixture("polyglot/web")).languages,
+        vec![Language::Js]
+    );
+}
+
+#[test]
+fn detect_typescript_over_javascript() {
+    let actual = Project::detect(&fixture("typescript"));
+
+    assert_eq!(actual.languages, vec![Language::Ts]);
+}
+
+#[test]
+/// Gradle's kotlin build scripts are used by java projects too, so leave them to auto mode
+fn detect_java_and_kotlin_from_gradle() {
+    let actual = Project::detect(&PathBuf::from("test/fixtures/kotlin/app/src/main/kotlin"));
+
+    assert_eq!(actual.languages, vec![Language::Java, Language::Kotlin]);
+    assert_eq!(actual.language(None), Language::Auto);
+}
+
+#[test]
+fn detect_java_and_kotlin_from_groovy_gradle() {
+    let actual = Project::detect(&fixture("gradle"));
+
+    assert_eq!(actual.languages, vec![Language::Java, Language::Kotlin]);
+}
+
+#[test]
+fn detect_ruby_from_gemfile() {
+    let actual = Project::detect(&PathBuf::from("test/fixtures/ruby/app/models/billing"));
+
+    assert_eq!(actual.languages, vec![Language::Ruby]);
+}
+
+#[test]
+/// sbt projects can mix java and scala, so are searched in both with auto mode
+fn detect_sbt_as_scala_and_java() {
+    let actual = Project::detect(&fixture("sbt/src/main/java"));
+
+    assert_eq!(actual.languages, vec![Language::Scala, Language::Java]);
+    assert_eq!(actual.language(None), Language::Auto);
+}
+
+#[test]
+fn detect_nothing() {
+    let dir = env::temp_dir().join(format!("qf-project-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let actual = Project::detect(&dir);
+    fs::remove_dir(&dir).unwrap();
+
+    assert_eq!(actual, Project::default());
+}
+
+#[test]
+/// The project's language should only stand in when no language is given, and only if there's
+/// just the one; an explicit auto keeps auto mode
+fn resolve_language() {
+    let rust = Project::detect(&fixture("rust"));
+    let polyglot = Project::detect(&fixture("polyglot"));
+
+    assert_eq!(rust.language(None), Language::Rust);
+    assert_eq!(rust.language(Some(&Language::Auto)), Language::Auto);
+    assert_eq!(rust.language(Some(&Language::Scala)), Language::Scala);
+    assert_eq!(polyglot.language(None), Language::Auto);
+    assert_eq!(Project::default().language(None), Language::Auto);
+}
+
+#[test]
+/// Without a language, the search should cover all the project's; auto covers everything
+fn resolve_search_languages() {
+    let polyglot = Project::detect(&fixture("polyglot"));
+    let class = SearchMode::Class;
+
+    assert_eq!(
+        polyglot.search_languages(&class, None),
+        vec![Language::Go, Language::Python]
+    );
+    assert_eq!(
+        polyglot.search_languages(&class, Some(&Language::Auto)),
+        vec![Language::Auto]
+    );
+    assert_eq!(
+        polyglot.search_languages(&class, Some(&Language::Rust)),
+        vec![Language::Rust]
+    );
+    assert_eq!(Project::default().search_languages(&class, None), Vec::<Language>::new());
+}
+
+#[test]
+/// Files and usages should be searched for everywhere unless a language is asked for
+fn resolve_search_languages_anywhere() {
+    let rust = Project::detect(&fixture("rust"));
+
+    for mode in [SearchMode::File, SearchMode::AllUsage] {
+        assert_eq!(rust.search_languages(&mode, None), Vec::<Language>::new());
+        assert_eq!(
+            rust.search_languages(&mode, Some(&Language::Rust)),
+            vec![Language::Rust]
+        );
+    }
+}
+
+#[test]
+fn resolve_index_languages() {
+    let polyglot = Project::detect(&fixture("polyglot"));
+
+    assert_eq!(
+        polyglot.index_languages(&Language::Auto),
+        vec![Language::Go, Language::Python]
+    );
+    assert_eq!(
+        polyglot.index_languages(&Language::Rust),
+        vec![Language::Rust]
+    );
+    assert_eq!(
+        Project::default().index_languages(&Language::Auto),
+        Vec::<Language>::new()
+    );
+}
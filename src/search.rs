@@ -1,40 +1,64 @@
 #[cfg(test)]
 mod tests;
 
+mod dialect;
+pub(crate) mod statement;
 mod strategy;
+mod zeitwerk;
 
+use std::collections::HashMap;
 use std::num::ParseIntError;
 
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::ag::{Ag, AgError};
 use crate::args::{Language, SearchMode};
-use crate::search::strategy::{get_strategy, SearchStrategy};
+use crate::backend::{BackendError, SearchBackend};
+use crate::project::Project;
+use crate::search::statement::{
+    continuation_pattern, find_package, find_statement, header_imports, is_header,
+    multiline_import_language, multiline_imports, package_imports,
+};
+use crate::search::strategy::{get_file_strategy, SearchStrategy};
+use crate::search::zeitwerk::constant_path;
 use crate::sort::sort_hits;
 
+pub use crate::search::dialect::Dialect;
+pub use crate::search::strategy::get_strategy;
+
 #[derive(Error, Debug)]
 pub enum SearchError {
-    #[error("Ag error: {0}")]
-    Ag(#[from] AgError),
-    #[error("Hit fragment count error")]
-    HitFragmentCount,
+    #[error("Search backend error: {0}")]
+    Backend(#[from] BackendError),
+    #[error("Hit fragment count error: {0:?}")]
+    HitFragmentCount(String),
     #[error("Error parsing line/col from ag: {0}")]
     HitParseError(#[from] ParseIntError),
+    #[error("Invalid search pattern: {0}")]
+    Pattern(#[from] regex::Error),
 }
 
 type Result<T> = std::result::Result<T, SearchError>;
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DetectedLanguage {
+    C,
+    Cpp,
     Go,
+    Java,
     Js,
+    Kotlin,
     Python,
+    Ruby,
     Rust,
     Scala,
+    Ts,
     Unknown,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct Hit {
     pub term: String,
     pub filename: String,
@@ -44,39 +68,68 @@ pub struct Hit {
     pub lang: DetectedLanguage,
 }
 
-fn detect_language(filename: &str) -> DetectedLanguage {
+pub(crate) fn detect_language(filename: &str) -> DetectedLanguage {
     match filename.split(".").last().map(|s| s.to_lowercase()) {
         Some(ext) => match ext.as_str() {
+            "c" | "h" => DetectedLanguage::C,
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => DetectedLanguage::Cpp,
             "go" => DetectedLanguage::Go,
-            "js" => DetectedLanguage::Js,
+            "java" => DetectedLanguage::Java,
+            "cjs" | "js" | "jsx" | "mjs" => DetectedLanguage::Js,
+            "kt" | "kts" => DetectedLanguage::Kotlin,
             "py" => DetectedLanguage::Python,
+            "rake" | "rb" => DetectedLanguage::Ruby,
             "rs" => DetectedLanguage::Rust,
             "sbt" | "sc" | "scala" => DetectedLanguage::Scala,
+            "cts" | "mts" | "ts" | "tsx" => DetectedLanguage::Ts,
             _ => DetectedLanguage::Unknown,
         },
         _ => DetectedLanguage::Unknown,
     }
 }
 
+impl DetectedLanguage {
+    /// The language hint to search with for files in this language
+    pub fn language(&self) -> Language {
+        match *self {
+            DetectedLanguage::C => Language::C,
+            DetectedLanguage::Cpp => Language::Cpp,
+            DetectedLanguage::Go => Language::Go,
+            DetectedLanguage::Java => Language::Java,
+            DetectedLanguage::Js => Language::Js,
+            DetectedLanguage::Kotlin => Language::Kotlin,
+            DetectedLanguage::Python => Language::Python,
+            DetectedLanguage::Ruby => Language::Ruby,
+            DetectedLanguage::Rust => Language::Rust,
+            DetectedLanguage::Scala => Language::Scala,
+            DetectedLanguage::Ts => Language::Ts,
+            DetectedLanguage::Unknown => Language::Auto,
+        }
+    }
+}
+
 impl Hit {
-    /// During a regular search we get filename, line, col, and then the hit text
+    /// During a regular search we get filename, line, col, and then the hit text. The filename is
+    /// NUL-terminated so that it can contain colons; line and col may be separated by either.
     fn parse(line: &str, term: &str) -> Result<Hit> {
-        let pieces: Vec<&str> = line.split(":").collect();
+        let (filename, rest) = line
+            .split_once('\0')
+            .ok_or_else(|| SearchError::HitFragmentCount(line.to_string()))?;
 
-        if pieces.len() < 4 {
-            return Err(SearchError::HitFragmentCount);
-        }
+        let pieces: Vec<&str> = rest.splitn(3, [':', '\0']).collect();
 
-        let filename = pieces[0].to_string();
+        if filename.is_empty() || pieces.len() < 3 {
+            return Err(SearchError::HitFragmentCount(line.to_string()));
+        }
 
-        return Ok(Hit {
+        Ok(Hit {
             term: term.to_string(),
-            filename: filename.clone(),
-            line: Some(pieces[1].parse::<u64>()?),
-            col: Some(pieces[2].parse::<u64>()?),
-            text: pieces[3..].join(":"),
-            lang: detect_language(&filename),
-        });
+            filename: filename.to_string(),
+            line: Some(pieces[0].parse::<u64>()?),
+            col: Some(pieces[1].parse::<u64>()?),
+            text: pieces[2].to_string(),
+            lang: detect_language(filename),
+        })
     }
 
     /// When filenames are searched, all we get is the filename
@@ -93,55 +146,259 @@ impl Hit {
 }
 
 pub struct Search {
-    ag: Ag,
+    backend: Box<dyn SearchBackend>,
     mode: SearchMode,
     lang: Language,
     strategy: SearchStrategy,
+    project: Project,
+    languages: Vec<Language>,
 }
 
 impl Search {
-    pub fn new(ag: Ag, mode: &SearchMode, lang: &Language) -> Search {
+    pub fn new(backend: Box<dyn SearchBackend>, mode: &SearchMode, lang: &Language) -> Search {
         Search {
-            ag: ag,
+            backend,
             mode: mode.clone(),
             lang: lang.clone(),
             strategy: get_strategy(lang),
+            project: Project::default(),
+            languages: vec![lang.clone()],
+        }
+    }
+
+    /// Search the given project, whose languages decide which of them an auto search can be in
+    pub fn with_project(mut self, project: Project) -> Search {
+        self.project = project;
+        self
+    }
+
+    /// Search files in the given languages instead of the search's own, e.g. those of the project
+    /// when no language was asked for. None (or auto) means every file.
+    pub fn with_languages(mut self, languages: Vec<Language>) -> Search {
+        self.languages = languages;
+        self
+    }
+
+    /// Run a pattern over the project, parsing each line of output as a hit
+    fn search_lines(&self, pattern: &str, term: &str) -> Result<Vec<Hit>> {
+        let output = self.backend.search(pattern, false, &self.languages)?;
+
+        output
+            .split('\n')
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Hit::parse(line, term))
+            .collect()
+    }
+
+    /// In auto mode, the search finds anything which any language would count as a match, so each
+    /// hit is checked against the strategy for the language of the file it was found in
+    fn filter_by_language(&self, mode: &SearchMode, term: &str, hits: Vec<Hit>) -> Vec<Hit> {
+        if self.lang != Language::Auto || matches!(mode, SearchMode::AllUsage | SearchMode::File) {
+            return hits;
+        }
+
+        let mut patterns: HashMap<DetectedLanguage, Option<Regex>> = HashMap::new();
+
+        hits.into_iter()
+            .filter(|h| {
+                let re = patterns.entry(h.lang.clone()).or_insert_with(|| {
+                    let strategy = get_file_strategy(&h.lang);
+                    Regex::new(&strategy.get_pattern(mode, term, Dialect::Rust)).ok()
+                });
+
+                re.as_ref().map(|r| r.is_match(&h.text)).unwrap_or(true)
+            })
+            .collect()
+    }
+
+    /// Imports can be split over several lines, which a line-based search can't see as a whole.
+    /// Lines which look like they might be inside one are searched for too, and the statement
+    /// around each hit read from its file, so that the hit carries the whole statement. Lines
+    /// which turn out not to be part of an import are dropped. There's no need to look for such
+    /// lines if none of the languages searched split imports that way.
+    fn expand_imports(&self, term: &str, hits: Vec<Hit>) -> Result<Vec<Hit>> {
+        let continues = self.languages.is_empty()
+            || self.languages.iter().any(multiline_import_language);
+        let candidates = if continues {
+            let pattern = self.backend.dialect().build(&continuation_pattern(), term);
+            self.search_lines(&pattern, term)?
+        } else {
+            vec![]
+        };
+        let continued: Vec<Hit> = candidates
+            .into_iter()
+            .filter(|c| !hits.iter().any(|h| h.filename == c.filename && h.line == c.line))
+            .map(|c| {
+                // The pattern matches from the start of the line, but the indent is no use
+                let indent = c.text.len() - c.text.trim_start().len();
+                Hit {
+                    col: Some(indent as u64 + 1),
+                    ..c
+                }
+            })
+            .collect();
+
+        let mut files: HashMap<String, Option<String>> = HashMap::new();
+        let mut results = vec![];
+
+        for (hit, confirmed) in hits
+            .into_iter()
+            .map(|h| (h, true))
+            .chain(continued.into_iter().map(|h| (h, false)))
+        {
+            if !multiline_imports(&hit.lang) {
+                if confirmed {
+                    results.push(hit);
+                }
+                continue;
+            }
+
+            let content = files
+                .entry(hit.filename.clone())
+                .or_insert_with(|| self.backend.read(&hit.filename).ok());
+            let statement = content.as_ref().and_then(|c| {
+                find_statement(c, hit.line.unwrap_or(1) as usize - 1, &hit.lang)
+            });
+
+            match statement {
+                Some(s) => results.push(Hit { text: s.text, ..hit }),
+                None if confirmed => results.push(hit),
+                None => (),
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// If nothing imports the term, languages which import definitions by their package can still
+    /// import it from wherever it's defined. Each hit is then the package declaration of a file
+    /// defining the term (as a class, or a function if it looks like one) at the top level, as
+    /// nested definitions aren't found by package alone. In c and c++, each hit is instead the
+    /// definition (or declaration) of the term in a header, to be included by its path.
+    fn definition_imports(&self, term: &str) -> Result<Vec<Hit>> {
+        let imports_definitions = |lang: &Language| package_imports(lang) || header_imports(lang);
+
+        if self.lang != Language::Auto && !imports_definitions(&self.lang) {
+            return Ok(vec![]);
+        }
+
+        let pattern = self.strategy.get_pattern(&SearchMode::Smart, term, self.backend.dialect());
+        let definitions = self.search_lines(&pattern, term)?;
+        let mut results: Vec<Hit> = vec![];
+
+        for h in self.filter_by_language(&SearchMode::Smart, term, definitions) {
+            if results.iter().any(|r| r.filename == h.filename) {
+                continue;
+            }
+
+            if header_imports(&h.lang.language()) {
+                if is_header(&h.filename) {
+                    results.push(h);
+                }
+                continue;
+            }
+
+            if !package_imports(&h.lang.language()) || h.text.starts_with(char::is_whitespace) {
+                continue;
+            }
+
+            if let Some(p) = self.backend.read(&h.filename).ok().and_then(|c| find_package(&c)) {
+                results.push(Hit {
+                    line: Some(p.start as u64 + 1),
+                    col: Some(1),
+                    text: p.text,
+                    ..h
+                });
+            }
         }
+
+        Ok(results)
     }
 
-    /// Get extra args to provide to ag -- primarily language, currently
-    fn get_ag_args(&self) -> Vec<String> {
-        match self.lang {
-            Language::Auto => vec![],
-            Language::Go => vec!["--go".to_string()],
-            Language::Js => vec!["--js".to_string()],
-            Language::Python => vec!["--python".to_string()],
-            Language::Rust => vec!["--rust".to_string()],
-            Language::Scala => vec!["--scala".to_string()],
+    /// Ruby constants are autoloaded from files named after them, so the file a constant should be
+    /// declared in is a hit too, even if the declaration can't be recognised as one on its own line
+    /// (e.g. a class nested in module blocks for its namespace, when searching by its full name).
+    /// The hit is on the declaration of the last part of the name if there is one, or failing
+    /// that the first mention of it. Only ruby searches, and auto searches of ruby projects, look.
+    fn constant_files(&self, term: &str, hits: Vec<Hit>) -> Result<Vec<Hit>> {
+        let ruby = match self.lang {
+            Language::Ruby => true,
+            Language::Auto => self.project.languages.contains(&Language::Ruby),
+            _ => false,
+        };
+        let path = match constant_path(term) {
+            Some(p) if ruby => p,
+            _ => return Ok(hits),
+        };
+
+        let name = term.rsplit("::").next().unwrap_or(term);
+        let declaration =
+            get_strategy(&Language::Ruby).get_pattern(&SearchMode::Class, name, Dialect::Rust);
+        let patterns = [
+            Regex::new(&declaration)?,
+            Regex::new(&format!(r"\b{}\b", regex::escape(name)))?,
+        ];
+
+        let pattern = self.backend.dialect().build("(?:^|/){}$", &path);
+        let files = self.backend.search(&pattern, true, &[Language::Ruby])?;
+        let mut results = hits;
+
+        for filename in files.split('\0').filter(|f| !f.trim().is_empty()) {
+            let content = self.backend.read(filename).unwrap_or_default();
+
+            let found = patterns.iter().find_map(|re| {
+                content
+                    .lines()
+                    .enumerate()
+                    .find_map(|(i, l)| re.find(l).map(|m| (i + 1, m.start() + 1, l)))
+            });
+            let (line, col, text) = found.unwrap_or((1, 1, content.lines().next().unwrap_or("")));
+
+            if results.iter().any(|h| h.filename == filename && h.line == Some(line as u64)) {
+                continue;
+            }
+
+            results.push(Hit {
+                term: term.to_string(),
+                filename: filename.to_string(),
+                line: Some(line as u64),
+                col: Some(col as u64),
+                text: text.to_string(),
+                lang: detect_language(filename),
+            });
         }
+
+        Ok(results)
     }
 
     /// Perform a search for a given term, based on the search config
     pub fn search(&self, term: &str) -> Result<Vec<Hit>> {
-        let mut results = {
-            self.ag
-                .ag(
-                    &self.strategy.get_pattern(&self.mode, &term),
-                    self.mode == SearchMode::File,
-                    &self.get_ag_args(),
-                )?
-                .split("\n")
-                .into_iter()
-                .filter(|line| !line.trim().is_empty())
-                .map(|line| {
-                    if self.mode == SearchMode::File {
-                        return Hit::parse_filename(line, term).unwrap();
-                    }
-
-                    // FIXME
-                    Hit::parse(line, term).unwrap()
-                })
-                .collect()
+        let pattern = self.strategy.get_pattern(&self.mode, term, self.backend.dialect());
+
+        let mut results = match self.mode {
+            SearchMode::File => self
+                .backend
+                .search(&pattern, true, &self.languages)?
+                .split('\0')
+                .filter(|f| !f.trim().is_empty())
+                .map(|f| Hit::parse_filename(f, term))
+                .collect::<Result<Vec<Hit>>>()?,
+            SearchMode::Import => {
+                let hits = self.search_lines(&pattern, term)?;
+                let hits = self.filter_by_language(&self.mode, term, hits);
+                let hits = self.expand_imports(term, hits)?;
+
+                if hits.is_empty() {
+                    self.definition_imports(term)?
+                } else {
+                    hits
+                }
+            }
+            SearchMode::Class | SearchMode::Smart => {
+                let hits = self.search_lines(&pattern, term)?;
+                self.constant_files(term, self.filter_by_language(&self.mode, term, hits))?
+            }
+            _ => self.filter_by_language(&self.mode, term, self.search_lines(&pattern, term)?),
         };
         sort_hits(&mut results, &self.mode);
 
@@ -0,0 +1,239 @@
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// The flavours of regex spoken by the various search backends
+///
+/// Strategy patterns are written as templates in a small subset of PCRE -- (?: groups, \h, \b,
+/// escaped punctuation and character classes -- with {} standing in for the search term. Each
+/// dialect knows how to render that template into something its engine will accept.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Dialect {
+    /// PCRE, as used by ag and git grep -P
+    Pcre,
+    /// The regex crate's syntax, as used by rg and the native search
+    Rust,
+    /// POSIX extended regex, as used by git grep -E
+    Posix,
+}
+
+/// A single member of a character class
+enum ClassItem {
+    Literal(char),
+    Range(char, char),
+    Named(String),
+}
+
+/// Characters with special meaning in a POSIX ERE outside of a bracket expression
+const POSIX_SPECIAL: &str = r".[]()*+?{}|^$\";
+
+/// Word boundaries before and after a word. \b is a GNU extension, which BSD regex would read
+/// as a literal b, so POSIX patterns match the character either side of the word instead.
+const POSIX_WORD_START: &str = "(^|[^[:alnum:]_])";
+const POSIX_WORD_END: &str = "([^[:alnum:]_]|$)";
+
+impl Dialect {
+    /// Escape a term so that it's matched literally
+    pub fn escape(&self, term: &str) -> String {
+        match *self {
+            Dialect::Pcre => term
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_punctuation() {
+                        format!("\\{c}")
+                    } else {
+                        c.to_string()
+                    }
+                })
+                .collect(),
+            Dialect::Rust => regex::escape(term),
+            Dialect::Posix => term
+                .chars()
+                .map(|c| {
+                    if POSIX_SPECIAL.contains(c) {
+                        format!("\\{c}")
+                    } else {
+                        c.to_string()
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Render a strategy template in this dialect, substituting the escaped term for {}
+    pub fn build(&self, template: &str, term: &str) -> String {
+        self.render(template, &self.escape(term))
+    }
+
+    /// Render a strategy template in this dialect, substituting a pattern (already written in
+    /// this dialect) for {}
+    pub fn render(&self, template: &str, pattern: &str) -> String {
+        let chars: Vec<char> = template.chars().collect();
+        let mut res = String::new();
+        let mut i = 0;
+
+        while i < chars.len() {
+            match chars[i] {
+                '{' if chars.get(i + 1) == Some(&'}') => {
+                    res.push_str(pattern);
+                    i += 2;
+                }
+                '(' if chars.get(i + 1) == Some(&'?') && chars.get(i + 2) == Some(&':') => {
+                    res.push_str(self.group());
+                    i += 3;
+                }
+                '\\' if *self == Dialect::Posix && chars.get(i + 1) == Some(&'b') => {
+                    // A boundary before anything which could start a word is the start of one
+                    let starts_word = match chars.get(i + 2) {
+                        Some('\\') => matches!(chars.get(i + 3), Some('w' | 'd')),
+                        Some(&c) => c.is_alphanumeric() || "_{([".contains(c),
+                        None => false,
+                    };
+                    res.push_str(if starts_word { POSIX_WORD_START } else { POSIX_WORD_END });
+                    i += 2;
+                }
+                '\\' if i + 1 < chars.len() => {
+                    res.push_str(&self.escaped(chars[i + 1]));
+                    i += 2;
+                }
+                '[' => {
+                    let (class, end) = self.class(&chars, i);
+                    res.push_str(&class);
+                    i = end;
+                }
+                c => {
+                    res.push(c);
+                    i += 1;
+                }
+            }
+        }
+
+        res
+    }
+
+    /// Opening for a non-capturing group; POSIX has no such thing so we capture regardless
+    fn group(&self) -> &'static str {
+        match *self {
+            Dialect::Posix => "(",
+            _ => "(?:",
+        }
+    }
+
+    /// Render an escape sequence found outside a character class
+    fn escaped(&self, c: char) -> String {
+        match (*self, c) {
+            (Dialect::Pcre, _) => format!("\\{c}"),
+            (_, 'h') => "[[:blank:]]".to_string(),
+            (Dialect::Posix, 'd') => "[[:digit:]]".to_string(),
+            (Dialect::Posix, 's') => "[[:space:]]".to_string(),
+            (Dialect::Posix, 't') => "\t".to_string(),
+            (Dialect::Posix, 'w') => "[[:alnum:]_]".to_string(),
+            (Dialect::Rust, c) if c.is_ascii_punctuation() => regex::escape(&c.to_string()),
+            (Dialect::Posix, c) if c.is_ascii_punctuation() => self.escape(&c.to_string()),
+            (_, c) => format!("\\{c}"),
+        }
+    }
+
+    /// Parse the character class starting at index start and render it in this dialect,
+    /// returning the rendered class and the index just past its end
+    fn class(&self, chars: &[char], start: usize) -> (String, usize) {
+        let mut i = start + 1;
+        let negated = chars.get(i) == Some(&'^');
+        if negated {
+            i += 1;
+        }
+
+        let first = i;
+        let mut items = vec![];
+
+        // A ] straight after the opening bracket is a literal rather than the end of the class
+        while i < chars.len() && (chars[i] != ']' || i == first) {
+            match chars[i] {
+                '\\' if i + 1 < chars.len() => {
+                    i += 1;
+                    items.extend(class_escape(chars[i]));
+                }
+                '[' if chars.get(i + 1) == Some(&':') => {
+                    let close = (i + 2..chars.len() - 1)
+                        .find(|&j| chars[j] == ':' && chars[j + 1] == ']')
+                        .unwrap_or(chars.len() - 2);
+                    items.push(ClassItem::Named(chars[i + 2..close].iter().collect()));
+                    i = close + 1;
+                }
+                c if chars.get(i + 1) == Some(&'-') && chars.get(i + 2).is_some_and(|&e| e != ']') => {
+                    items.push(ClassItem::Range(c, chars[i + 2]));
+                    i += 2;
+                }
+                c => items.push(ClassItem::Literal(c)),
+            }
+            i += 1;
+        }
+
+        let end = (i + 1).min(chars.len());
+
+        // The template is already PCRE, so there's nothing to translate
+        if *self == Dialect::Pcre {
+            return (chars[start..end].iter().collect(), end);
+        }
+
+        let mut res = String::from("[");
+        if negated {
+            res.push('^');
+        }
+
+        if *self == Dialect::Rust {
+            for item in &items {
+                match item {
+                    ClassItem::Literal(c) => res.push_str(&rust_class_char(*c)),
+                    ClassItem::Range(a, b) => {
+                        res.push_str(&format!("{}-{}", rust_class_char(*a), rust_class_char(*b)))
+                    }
+                    ClassItem::Named(n) => res.push_str(&format!("[:{n}:]")),
+                }
+            }
+        } else {
+            // Bracket expressions have no escapes in POSIX, so the awkward characters need to be
+            // placed where they can't be mistaken for syntax: ] first, then [, ^ and - last
+            let has = |c: char| items.iter().any(|item| matches!(item, ClassItem::Literal(l) if *l == c));
+
+            if has(']') {
+                res.push(']');
+            }
+            for item in &items {
+                match item {
+                    ClassItem::Literal(']' | '[' | '^' | '-') => (),
+                    ClassItem::Literal(c) => res.push(*c),
+                    ClassItem::Range(a, b) => res.push_str(&format!("{a}-{b}")),
+                    ClassItem::Named(n) => res.push_str(&format!("[:{n}:]")),
+                }
+            }
+            for c in ['[', '^', '-'] {
+                if has(c) {
+                    res.push(c);
+                }
+            }
+        }
+
+        res.push(']');
+        (res, end)
+    }
+}
+
+/// Interpret an escape sequence found within a character class
+fn class_escape(c: char) -> Vec<ClassItem> {
+    match c {
+        'h' => vec![ClassItem::Named("blank".to_string())],
+        'd' => vec![ClassItem::Named("digit".to_string())],
+        's' => vec![ClassItem::Named("space".to_string())],
+        'w' => vec![ClassItem::Named("alnum".to_string()), ClassItem::Literal('_')],
+        't' => vec![ClassItem::Literal('\t')],
+        c => vec![ClassItem::Literal(c)],
+    }
+}
+
+/// Escape a character for use within a character class in the regex crate
+fn rust_class_char(c: char) -> String {
+    match c {
+        '\\' | ']' | '[' | '^' | '-' | '&' | '~' => format!("\\{c}"),
+        _ => c.to_string(),
+    }
+}
@@ -0,0 +1,216 @@
+use super::*;
+
+use regex::Regex;
+
+const DIALECTS: [Dialect; 3] = [Dialect::Pcre, Dialect::Rust, Dialect::Posix];
+
+/// The grep flag selecting the engine for a dialect, or None if it's checked in-process
+fn grep_flag(dialect: Dialect) -> Option<&'static str> {
+    match dialect {
+        Dialect::Rust => None,
+        Dialect::Pcre => Some("-P"),
+        Dialect::Posix => Some("-E"),
+    }
+}
+
+/// The dialects whose engine is available to check patterns against. PCRE and POSIX patterns are
+/// checked with GNU grep, so are skipped where it's missing or built without -P.
+pub(crate) fn dialects() -> Vec<Dialect> {
+    use std::process::{Command, Stdio};
+
+    DIALECTS
+        .into_iter()
+        .filter(|d| {
+            let flag = match grep_flag(*d) {
+                Some(f) => f,
+                None => return true,
+            };
+            let available = Command::new("grep")
+                .args([flag, "-e", "x"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|s| matches!(s.code(), Some(0) | Some(1)))
+                .unwrap_or(false);
+
+            if !available {
+                eprintln!("grep {flag} is unavailable, skipping {d:?} patterns");
+            }
+            available
+        })
+        .collect()
+}
+
+#[test]
+fn escape_pcre() {
+    let actual = Dialect::Pcre.escape("foo.bar<T>");
+    let expected = r"foo\.bar\<T\>".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn escape_rust() {
+    let actual = Dialect::Rust.escape("foo.bar<T>");
+    let expected = r"foo\.bar<T>".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Only ERE metacharacters may be escaped; GNU gives \< and friends a meaning of their own
+fn escape_posix() {
+    let actual = Dialect::Posix.escape("foo.bar<T>");
+    let expected = r"foo\.bar<T>".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Terms containing a PCRE quote terminator must still be matched literally
+fn escape_pcre_quote_terminator() {
+    let actual = Dialect::Pcre.escape(r"a\Eb");
+    let expected = r"a\\Eb".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn build_pcre_unchanged() {
+    let template = r"(?:class|trait) {}\h*(?:[\[\(\{{: ]|$)";
+    let actual = Dialect::Pcre.build(template, "Foo");
+    let expected = r"(?:class|trait) Foo\h*(?:[\[\(\{{: ]|$)".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn build_rust() {
+    let template = r"(?:def|fn) {}\h*[\<\[\(: ]";
+    let actual = Dialect::Rust.build(template, "foo");
+    let expected = r"(?:def|fn) foo[[:blank:]]*[<\[(: ]".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn build_posix() {
+    let template = r"(?:def|fn) (?:\(.+\) )?{}\h*[\<\[\(: ]";
+    let actual = Dialect::Posix.build(template, "foo");
+    let expected = r"(def|fn) (\(.+\) )?foo[[:blank:]]*[<(: []".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// \b and \t are GNU extensions, so POSIX patterns need to spell them out
+fn build_posix_portable_escapes() {
+    let actual = Dialect::Posix.build(r"\b{}\b\t", "foo");
+    let expected = "(^|[^[:alnum:]_])foo([^[:alnum:]_]|$)\t".to_string();
+
+    assert_eq!(actual, expected);
+
+    if dialects().contains(&Dialect::Posix) {
+        let pattern = Dialect::Posix.build(r"\b{}\b", "foo");
+
+        assert_eq!(grep(Dialect::Posix, &pattern, "foo"), Some(true));
+        assert_eq!(grep(Dialect::Posix, &pattern, "a (foo) b"), Some(true));
+        assert_eq!(grep(Dialect::Posix, &pattern, "afoo foob"), Some(false));
+    }
+}
+
+#[test]
+/// POSIX bracket expressions can't escape anything, so ] and - need to be moved into position
+fn build_posix_awkward_class() {
+    let actual = Dialect::Posix.build(r"[\-a\]\\]{}", "x");
+    let expected = r"[]a\-]x".to_string();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn build_ranges_and_named_classes() {
+    let template = r"[A-Z\w][[:digit:]]";
+
+    assert_eq!(
+        Dialect::Rust.build(template, ""),
+        r"[A-Z[:alnum:]_][[:digit:]]"
+    );
+    assert_eq!(
+        Dialect::Posix.build(template, ""),
+        r"[A-Z[:alnum:]_][[:digit:]]"
+    );
+}
+
+#[test]
+/// The regex crate should match the same things PCRE would
+fn build_rust_matches() {
+    let template = r#"(?:import|use).*[\.\{{,:/" ]{}(?:[\{{\}},;/" ]|$)"#;
+    let re = Regex::new(&Dialect::Rust.build(template, "GameId")).unwrap();
+
+    assert!(re.is_match("use crate::models::game::{GameDetails, GameId, SteamPlaytime};"));
+    assert!(!re.is_match("use crate::models::game::{GameDetails, GameIdent};"));
+}
+
+#[test]
+/// Escaped terms should match themselves and nothing else, whatever they contain
+fn escape_round_trip() {
+    for dialect in dialects() {
+        for term in [
+            "foo.bar",
+            "a+b",
+            "(x)",
+            "[y]",
+            "{z}",
+            "a|b",
+            "^$",
+            r"back\slash",
+        ] {
+            let pattern = dialect.build("^{}$", term);
+            let hit = grep(dialect, &pattern, term);
+            let miss = grep(dialect, &pattern, "something else");
+
+            assert_eq!(
+                (hit, miss),
+                (Some(true), Some(false)),
+                "{dialect:?}: {pattern}"
+            );
+        }
+    }
+}
+
+/// Run a pattern over a single line of input with the engine for the dialect, returning whether
+/// it matched, or None if the pattern was rejected
+pub(crate) fn grep(dialect: Dialect, pattern: &str, line: &str) -> Option<bool> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let flag = match grep_flag(dialect) {
+        Some(f) => f,
+        None => return Regex::new(pattern).ok().map(|re| re.is_match(line)),
+    };
+
+    let mut child = Command::new("grep")
+        .arg(flag)
+        .arg("-e")
+        .arg(pattern)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(format!("{line}\n").as_bytes())
+        .unwrap();
+
+    match child.wait().unwrap().code() {
+        Some(0) => Some(true),
+        Some(1) => Some(false),
+        _ => None,
+    }
+}
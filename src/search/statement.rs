@@ -0,0 +1,198 @@
+#[cfg(test)]
+mod tests;
+
+use regex::Regex;
+
+use crate::args::Language;
+use crate::search::DetectedLanguage;
+
+/// A line in the middle of a bracketed javascript, typescript, python, scala or rust import, made
+/// up of nothing but names, separators and brackets
+const BRACKETED_PATTERN: &str =
+    r#"[\w\h\.:,\{\}\(\)\*]*(?:^|[\h\.:,\{\(]){}(?:\h+as\h+\w+)?\h*(?:[,\}\);\\]|$)"#;
+
+/// A line in a go import block: a quoted package path, possibly aliased, whose package name is
+/// the term (give or take a go- prefix and version suffix), or which is aliased to the term
+const GO_SPEC_PATTERN: &str =
+    r#"\h*(?:(?:\w+|\.)\h+)?"(?:[^"]*/)?(?:go-)?{}(?:[\.-][^"/]*)?(?:/v\d+)?"|\h*{}\h+""#;
+
+/// Finds a term on a line which may be part of an import statement split over several lines.
+/// Whether it really is part of an import is checked against the statement around it once the
+/// file has been read.
+pub(crate) fn continuation_pattern() -> String {
+    format!("^(?:{BRACKETED_PATTERN}|{GO_SPEC_PATTERN})")
+}
+
+/// The most lines we'll look back through to find the start of a statement
+const MAX_LINES: usize = 100;
+
+/// An import statement, joined onto a single line
+#[derive(Debug, PartialEq)]
+pub(crate) struct Statement {
+    /// Lines the statement starts and ends on, counting from 0
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// Whether imports in a language can be split over several lines in a way we can follow
+pub(crate) fn multiline_imports(lang: &DetectedLanguage) -> bool {
+    matches!(
+        lang,
+        DetectedLanguage::Go
+            | DetectedLanguage::Js
+            | DetectedLanguage::Python
+            | DetectedLanguage::Rust
+            | DetectedLanguage::Scala
+            | DetectedLanguage::Ts
+    )
+}
+
+/// Whether a search in a language can find imports split over several lines, auto meaning any
+pub(crate) fn multiline_import_language(lang: &Language) -> bool {
+    matches!(
+        lang,
+        Language::Auto
+            | Language::Go
+            | Language::Js
+            | Language::Python
+            | Language::Rust
+            | Language::Scala
+            | Language::Ts
+    )
+}
+
+/// Whether a language imports definitions by the package declared at the top of the file they're
+/// in, so that they can be imported without having been imported anywhere else
+pub(crate) fn package_imports(lang: &Language) -> bool {
+    matches!(lang, Language::Java | Language::Kotlin)
+}
+
+/// Whether a language imports definitions by including the header file they're declared in
+pub(crate) fn header_imports(lang: &Language) -> bool {
+    matches!(lang, Language::C | Language::Cpp)
+}
+
+/// Whether a file is a c or c++ header, which can be included
+pub(crate) fn is_header(filename: &str) -> bool {
+    matches!(
+        filename.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).as_deref(),
+        Some("h" | "hh" | "hpp" | "hxx")
+    )
+}
+
+/// Find the package declaration of a file, if it has one
+pub(crate) fn find_package(content: &str) -> Option<Statement> {
+    let re = Regex::new(r"^\s*package\s+[\w\.]+").unwrap();
+
+    content
+        .lines()
+        .enumerate()
+        .find(|(_, l)| re.is_match(l))
+        .map(|(i, l)| Statement {
+            start: i,
+            end: i,
+            text: l.to_string(),
+        })
+}
+
+/// Strip any trailing comment from a line, leaving comment markers inside quotes alone (e.g. a
+/// url given as a javascript module specifier)
+fn code<'a>(line: &'a str, lang: &DetectedLanguage) -> &'a str {
+    let marker = match lang {
+        DetectedLanguage::Python | DetectedLanguage::Ruby => "#",
+        _ => "//",
+    };
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => (),
+            None if line[i..].starts_with(marker) => return &line[..i],
+            None if matches!(c, '"' | '\'' | '`') => quote = Some(c),
+            None => (),
+        }
+    }
+
+    line
+}
+
+/// Find the line an import statement starting on the given line ends on: the one where all its
+/// brackets are closed and, in rust, where it's terminated, unless continued with a backslash
+fn statement_end(lines: &[&str], start: usize, lang: &DetectedLanguage) -> Option<usize> {
+    let mut depth = 0;
+
+    for (i, line) in lines.iter().enumerate().skip(start).take(MAX_LINES) {
+        let line = code(line, lang);
+
+        for c in line.chars() {
+            match c {
+                '{' | '(' => depth += 1,
+                '}' | ')' => depth -= 1,
+                _ => (),
+            }
+        }
+
+        let continued = line.trim_end().ends_with('\\');
+        let terminated = *lang != DetectedLanguage::Rust || line.contains(';');
+
+        if depth <= 0 && !continued && terminated {
+            return Some(i);
+        }
+    }
+
+    None
+}
+
+/// Join the lines of a statement into one, tidying up the whitespace and trailing commas left
+/// around brackets, so it reads as if it had been written on one line in the first place
+fn join(lines: &[&str], lang: &DetectedLanguage) -> String {
+    let joined = lines
+        .iter()
+        .map(|l| code(l, lang).trim().trim_end_matches('\\').trim())
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let open = Regex::new(r"([\{\(])\s+").unwrap();
+    let close = Regex::new(r",?\s*([\}\)])").unwrap();
+    let joined = open.replace_all(&joined, "$1");
+
+    close.replace_all(&joined, "$1").into_owned()
+}
+
+/// Find the import statement which includes the given line of a file, if there is one
+pub(crate) fn find_statement(
+    content: &str,
+    line: usize,
+    lang: &DetectedLanguage,
+) -> Option<Statement> {
+    let start_re =
+        Regex::new(r"^\s*(?:pub(?:\([^)]*\))?\s+)?(?:from\s+[\w\.]+\s+)?(?:import|use)\b").unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+
+    if line >= lines.len() {
+        return None;
+    }
+
+    let start = (line.saturating_sub(MAX_LINES)..=line)
+        .rev()
+        .find(|&i| start_re.is_match(lines[i]))?;
+    let end = statement_end(&lines, start, lang)?;
+
+    if end < line {
+        return None;
+    }
+
+    let text = if start == end {
+        lines[start].to_string()
+    } else {
+        join(&lines[start..=end], lang)
+    };
+
+    Some(Statement { start, end, text })
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+const PYTHON: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/test/fixtures/python/multiline.py"
+));
+const RUST: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/test/fixtures/rust/multiline.rs"
+));
+
+#[test]
+fn find_python_statement() {
+    let expected = Statement {
+        start: 2,
+        end: 5,
+        text: "from s3_browser.paths import (S3Bucket as Bucket, S3Key)".to_string(),
+    };
+
+    for line in 2..=5 {
+        assert_eq!(
+            find_statement(PYTHON, line, &DetectedLanguage::Python).as_ref(),
+            Some(&expected)
+        );
+    }
+}
+
+#[test]
+/// Backslashes continue a python statement onto the next line
+fn find_python_statement_continued() {
+    let expected = Statement {
+        start: 6,
+        end: 7,
+        text: "from s3_browser.utils import human_size".to_string(),
+    };
+    let actual = find_statement(PYTHON, 7, &DetectedLanguage::Python);
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+fn find_rust_statement() {
+    let expected = Statement {
+        start: 0,
+        end: 3,
+        text: "use std::{fmt::{self, Display}, sync::Arc};".to_string(),
+    };
+    let actual = find_statement(RUST, 1, &DetectedLanguage::Rust);
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+fn find_scala_statement() {
+    let content = "import a.b.{\n  C,\n  D\n}\n\nobject E";
+    let expected = Statement {
+        start: 0,
+        end: 3,
+        text: "import a.b.{C, D}".to_string(),
+    };
+    let actual = find_statement(content, 2, &DetectedLanguage::Scala);
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+/// Comment markers in a module specifier are part of the statement, not the start of a comment
+fn find_js_statement_url_specifier() {
+    let content = "import {\n  x, // the x\n  y,\n} from \"https://deno.land/x/mod.ts\";\n";
+    let expected = Statement {
+        start: 0,
+        end: 3,
+        text: r#"import {x, y} from "https://deno.land/x/mod.ts";"#.to_string(),
+    };
+    let actual = find_statement(content, 1, &DetectedLanguage::Ts);
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+fn strip_comments_outside_quotes() {
+    let cases = [
+        ("use a::B; // comment", DetectedLanguage::Rust, "use a::B; "),
+        (r#"from "http://a/b" // c"#, DetectedLanguage::Js, r#"from "http://a/b" "#),
+        (r#"from 'a\'//b'"#, DetectedLanguage::Js, r#"from 'a\'//b'"#),
+        ("import a  # comment", DetectedLanguage::Python, "import a  "),
+        (r#"require "a#b" # c"#, DetectedLanguage::Ruby, r#"require "a#b" "#),
+    ];
+
+    for (line, lang, expected) in cases {
+        assert_eq!(code(line, &lang), expected);
+    }
+}
+
+#[test]
+/// A statement on a single line should be left exactly as it is
+fn find_single_line_statement() {
+    let content = "use a::B; // comment";
+    let expected = Statement {
+        start: 0,
+        end: 0,
+        text: content.to_string(),
+    };
+    let actual = find_statement(content, 0, &DetectedLanguage::Rust);
+
+    assert_eq!(actual, Some(expected));
+}
+
+#[test]
+/// Lines after the end of an import, or before any import, aren't part of one
+fn find_no_statement() {
+    assert_eq!(find_statement(RUST, 13, &DetectedLanguage::Rust), None);
+    assert_eq!(find_statement(PYTHON, 0, &DetectedLanguage::Python), None);
+    assert_eq!(find_statement(PYTHON, 100, &DetectedLanguage::Python), None);
+}
@@ -1,22 +1,57 @@
+mod c;
 mod go;
+mod java;
+mod js;
+mod kotlin;
+mod python;
+mod ruby;
+mod rust;
+mod scala;
+#[cfg(test)]
+mod tests;
+mod ts;
+
+use std::iter;
 
 use crate::args::{Language, SearchMode};
+use crate::search::{DetectedLanguage, Dialect};
 
 // This one is fairly complex due to different language patterns for imports:
-//   - After "import" or "use" we match everything up to one of [., {:/] to try to find the last
+//   - After "import" or "use" we match everything up to one of [., {(:/] to try to find the last
 //     separator and extract the symbol from the import. That's because scala/python/rust can use
-//     brace / commas for multi import blocks, rust uses :: as a separator, and go uses
-//     / as a separator. The space is because some imports will be simple and just a single term.
-//   - We then match on the literal symbol which will be quoted and replaced in {}, like \Qfoo\E
+//     brace / commas for multi import blocks, python can wrap them in parentheses, rust uses ::
+//     as a separator, and go uses / as a separator. The space is because some imports will be
+//     simple and just a single term.
+//   - We then match on the literal symbol, which will be escaped to suit the backend's regex
+//     dialect and substituted for {}
 //   - Finally we match and discard a character which can end the symbol: again a space, comma or
-//     close brace for scala/python/rust, a semicolon for rust (though also possible in python
+//     close bracket for scala/python/rust, a semicolon for rust (though also possible in python
 //     or scala) and a quotation mark for go.
 //
+// Statements split over several lines are picked up separately; see search::statement.
+//
 // N.B. if this gets any more complex then most likely it should be broken into individual regexes
 // for specific languages, especially as reqirements of one language may break those of another
 // language. In that case the language should be detected first, and then the right regex applied.
-const IMPORT_PATTERN: &str = r#"(?:import|use).*[\.\{{,:/" ]{}(?:[\{{\}},;/" ]|$)"#;
+const IMPORT_PATTERN: &str = r#"(?:import|use).*[\.\{{\(,:/" ]{}(?:[\{{\}}\),;/" ]|$)"#;
+
+/// Languages with a search strategy of their own, which auto mode combines
+const LANGUAGES: [Language; 11] = [
+    Language::C,
+    Language::Cpp,
+    Language::Go,
+    Language::Java,
+    Language::Js,
+    Language::Kotlin,
+    Language::Python,
+    Language::Ruby,
+    Language::Rust,
+    Language::Scala,
+    Language::Ts,
+];
 
+// The generic patterns below are a mix of keywords from every language, so are only used for files
+// in languages which have no strategy of their own.
 const CLASS_PATTERN: &str = {
     r#"(?:case class|class|trait|object|type|struct|impl|enum) {}\h*(?:[\[\(\{{: ]|$)"#
 };
@@ -25,21 +60,46 @@ const CLASS_PATTERN: &str = {
 const FUNCTION_PATTERN: &str = r#"(?:def|fn|function|func) (?:\(.+\) )?{}[\<\[\(: ]"#;
 
 trait SmartMode {
-    /// A pattern to use to "smartfind" a symbol as either a class or function
+    /// A pattern to use to "smartfind" a symbol as either a class or function, given the
+    /// language's class and function patterns.
     /// For most languages we search with a func pattern if it starts with a lowercase and a class
     /// pattern if it starts with uppercase, but some languages may have different patterns (go!)
-    fn get_pattern(&self, term: &str) -> String;
+    fn get_pattern(&self, term: &str, class: &str, function: &str) -> String;
 }
 pub struct DefaultSmartMode {}
 
 impl SmartMode for DefaultSmartMode {
     /// First lowercase == function, uppercase == class
-    fn get_pattern(&self, term: &str) -> String {
+    fn get_pattern(&self, term: &str, class: &str, function: &str) -> String {
         if term.chars().next().map(|c| c.is_lowercase()).unwrap_or(true) {
-            return FUNCTION_PATTERN.to_owned();
+            return function.to_owned();
         }
 
-        CLASS_PATTERN.to_owned()
+        class.to_owned()
+    }
+}
+
+/// Join patterns into one which matches anything any of them would, leaving out duplicates
+fn any_of(patterns: impl IntoIterator<Item = String>) -> String {
+    let mut unique: Vec<String> = vec![];
+
+    for p in patterns {
+        if !unique.contains(&p) {
+            unique.push(p);
+        }
+    }
+
+    format!("(?:{})", unique.join("|"))
+}
+
+/// Smart mode in auto mode, which has to find whatever the smart mode of any language would
+struct AutoSmartMode {
+    strategies: Vec<SearchStrategy>,
+}
+
+impl SmartMode for AutoSmartMode {
+    fn get_pattern(&self, term: &str, _class: &str, _function: &str) -> String {
+        any_of(self.strategies.iter().map(|s| s.smart_pattern(term)))
     }
 }
 
@@ -82,27 +142,81 @@ impl SearchStrategy {
         )
     }
 
-    /// Wrap the term in an appropriate regex depending on the search mode
-    pub fn get_pattern(&self, mode: &SearchMode, term: &str) -> String {
-        // Regex raw quote
-        let raw = format!("\\Q{}\\E", term);
+    /// The language of a file isn't known in auto mode until it's been found, so search for
+    /// anything which the strategy of any language (or the generic one) would find. Each hit
+    /// should then be checked against the strategy for the language it was found in.
+    fn auto() -> SearchStrategy {
+        let strategies: Vec<SearchStrategy> = iter::once(SearchStrategy::default())
+            .chain(LANGUAGES.iter().map(get_strategy))
+            .collect();
+        let combine = |pattern: fn(&SearchStrategy) -> &String| {
+            any_of(strategies.iter().map(|s| pattern(s).clone()))
+        };
 
+        SearchStrategy::new(
+            "{}",
+            "{}",
+            &combine(|s| &s.class_pattern),
+            &combine(|s| &s.function_pattern),
+            &combine(|s| &s.import_pattern),
+            Box::new(AutoSmartMode { strategies }),
+        )
+    }
+
+    fn smart_pattern(&self, term: &str) -> String {
+        self.smart_mode
+            .get_pattern(term, &self.class_pattern, &self.function_pattern)
+    }
+
+    /// Wrap the term in an appropriate regex depending on the search mode, written in the dialect
+    /// understood by the search backend
+    pub fn get_pattern(&self, mode: &SearchMode, term: &str, dialect: Dialect) -> String {
         let fmt = match *mode {
             SearchMode::AllUsage => &self.all_usage_pattern,
             SearchMode::Class => &self.class_pattern,
             SearchMode::File => &self.file_pattern,
             SearchMode::Function => &self.function_pattern,
             SearchMode::Import => &self.import_pattern,
-            SearchMode::Smart => &self.smart_mode.get_pattern(term),
+            SearchMode::Smart => &self.smart_pattern(term),
+        };
+
+        dialect.build(fmt, term)
+    }
+
+    /// A pattern matching any definition (or import) of the given kind, capturing the name, for
+    /// extracting every one from a file rather than searching for one in particular
+    pub fn get_definition_pattern(&self, mode: &SearchMode, dialect: Dialect) -> String {
+        let fmt = match *mode {
+            SearchMode::Class => &self.class_pattern,
+            SearchMode::Import => &self.import_pattern,
+            _ => &self.function_pattern,
         };
 
-        fmt.replace("{}", &raw)
+        dialect.render(fmt, r"(\w+)")
     }
 }
 
 pub fn get_strategy(lang: &Language) -> SearchStrategy {
     match *lang {
+        Language::Auto => SearchStrategy::auto(),
+        Language::C | Language::Cpp => c::get_strategy(),
         Language::Go => go::get_strategy(),
-        _ => SearchStrategy::default(),
+        Language::Java => java::get_strategy(),
+        Language::Js => js::get_strategy(),
+        Language::Kotlin => kotlin::get_strategy(),
+        Language::Python => python::get_strategy(),
+        Language::Ruby => ruby::get_strategy(),
+        Language::Rust => rust::get_strategy(),
+        Language::Scala => scala::get_strategy(),
+        Language::Ts => ts::get_strategy(),
+    }
+}
+
+/// The strategy for a file in the given language, falling back to the generic one for languages
+/// without a strategy of their own
+pub fn get_file_strategy(lang: &DetectedLanguage) -> SearchStrategy {
+    match lang {
+        DetectedLanguage::Unknown => SearchStrategy::default(),
+        lang => get_strategy(&lang.language()),
     }
 }
@@ -0,0 +1,54 @@
+use super::*;
+
+/// Structs, unions and enums (and in c++, classes and scoped enums) with a body or base list,
+/// leaving out forward declarations and uses of the type
+const C_TYPE_PATTERN: &str =
+    r#"(?:struct|class|union|enum(?:\h+class|\h+struct)?)\h+{}(?:\h+final)?\h*(?:[:\{]|$)"#;
+
+/// Typedefs of anything, including function pointers and anonymous structs (named as their body
+/// is closed), and c++ aliases
+const C_TYPEDEF_PATTERN: &str =
+    r#"typedef\h.*(?:\(\h*\*\h*{}\h*\)|[\h\*]{}\h*[;\[])|^\}\h*{}\h*;|using\h+{}\h*="#;
+
+/// Macros are types (or constants) unless they take arguments, in which case they're functions
+const C_MACRO_PATTERN: &str = r#"#\h*define\h+{}(?:\h|$)"#;
+const C_FUNCTION_MACRO_PATTERN: &str = r#"#\h*define\h+{}\("#;
+
+/// Functions defined or declared at the top level, possibly qualified by their class or namespace
+/// and possibly with their return type on the line above. A call at the top level is a rarity,
+/// and a pointer to a function is left to the typedef pattern.
+const C_FUNCTION_PATTERN: &str = r#"^(?:\w[\w\h\*&,<>:]*[\h\*&])?(?:\w+::)*~?{}\h*\((?:[^\*]|$)"#;
+
+/// Methods declared in a class body are indented like calls are, so have to have a return type
+/// which is a primitive or looks like a class, after any modifiers. A pointer or reference has
+/// to be attached to the type or the name, so as not to be mistaken for a multiplication.
+const C_MEMBER_PATTERN: &str = {
+    r#"(?:(?:virtual|static|inline|constexpr|const|unsigned|signed)\h+)*(?:void|bool|char|short|int|long|float|double|auto|\w+_t|std::[\w:]+|[\w:]*[A-Z][\w:]*)(?:<.*>)?(?:\h+[\*&]*|[\*&]+\h*){}\h*\("#
+};
+
+/// Nothing about a name says whether it's a type or a function in c or c++
+struct CSmartMode {}
+
+impl SmartMode for CSmartMode {
+    fn get_pattern(&self, _term: &str, class: &str, function: &str) -> String {
+        format!("(?:{class}|{function})")
+    }
+}
+
+/// Headers are included by path, which is named after the term if it's the name of the header
+/// (e.g. <vector>, or "widget.h" for widget). Names must match exactly, so a Widget class is
+/// included from the header defining it instead.
+const INCLUDE_PATTERN: &str = r#"#\h*include\h*[<"](?:[^>"]*/)?{}(?:\.\w+)?[>"]"#;
+
+/// c++ is mostly a superset of c, and the two are often mixed (not least in headers, which are
+/// shared), so they're searched the same way
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        &format!("(?:{C_TYPE_PATTERN}|{C_TYPEDEF_PATTERN}|{C_MACRO_PATTERN})"),
+        &format!("(?:{C_FUNCTION_PATTERN}|{C_MEMBER_PATTERN}|{C_FUNCTION_MACRO_PATTERN})"),
+        INCLUDE_PATTERN,
+        Box::new(CSmartMode {}),
+    )
+}
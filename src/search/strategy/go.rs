@@ -1,10 +1,23 @@
 use super::*;
 
+/// Go imports name a quoted package path, optionally aliased, and are used by the name of the
+/// package: the last element of the path, give or take a go- prefix and a version suffix. The
+/// path can also be aliased to the term itself.
+const GO_IMPORT_PATTERN: &str = {
+    r#"import.*[\h\(](?:(?:\w+|\.)\h+)?"(?:[^"]*/)?(?:go-)?{}(?:[\.-][^"/]*)?(?:/v\d+)?"|import.*[\h\(]{}\h+""#
+};
+
+/// Types are only ever defined with type, possibly with type parameters
+const GO_CLASS_PATTERN: &str = r#"type {}(?:[\h\[]|$)"#;
+
+/// Methods have their receiver between func and the name
+const GO_FUNCTION_PATTERN: &str = r#"func (?:\(.+\) )?{}[\[\(]"#;
+
 struct GoSmartMode {}
 
 impl SmartMode for GoSmartMode {
-    fn get_pattern(&self, _term: &str) -> String {
-        return format!("(?:{CLASS_PATTERN}|{FUNCTION_PATTERN})");
+    fn get_pattern(&self, _term: &str, class: &str, function: &str) -> String {
+        format!("(?:{class}|{function})")
     }
 }
 
@@ -12,9 +25,9 @@ pub(super) fn get_strategy() -> SearchStrategy {
     SearchStrategy::new(
         "{}",
         "{}",
-        CLASS_PATTERN,
-        FUNCTION_PATTERN,
-        IMPORT_PATTERN,
+        GO_CLASS_PATTERN,
+        GO_FUNCTION_PATTERN,
+        GO_IMPORT_PATTERN,
         Box::new(GoSmartMode {}),
     )
 }
@@ -0,0 +1,22 @@
+use super::*;
+
+/// Annotation types are declared with @interface, and records with their components
+const JAVA_CLASS_PATTERN: &str = r#"(?:class|@?interface|enum|record) {}\h*(?:[<\(\{ ]|$)"#;
+
+/// Methods are declared with a return type, after any modifiers and type parameters. The type has
+/// to be a primitive or look like a class (or type parameter) so that calls after return, new,
+/// throw, etc. aren't mistaken for declarations.
+const JAVA_FUNCTION_PATTERN: &str = {
+    r#"(?:void|boolean|byte|char|short|int|long|float|double|[\w\.]*[A-Z][\w\.]*(?:<.*>)?)(?:\[\])*\h+{}\h*\("#
+};
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        JAVA_CLASS_PATTERN,
+        JAVA_FUNCTION_PATTERN,
+        IMPORT_PATTERN,
+        Box::new(DefaultSmartMode {}),
+    )
+}
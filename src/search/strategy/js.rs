@@ -0,0 +1,22 @@
+use super::*;
+
+pub(super) const JS_CLASS_PATTERN: &str = r#"class {}\h*(?:[\{ ]|$)"#;
+
+/// Function declarations, including generators
+pub(super) const JS_FUNCTION_PATTERN: &str = r#"function\*?\h*{}\h*\("#;
+
+/// CommonJS modules are imported by assigning the result of require to a variable, or by
+/// destructuring it
+pub(super) const REQUIRE_PATTERN: &str =
+    r#"(?:const|let|var)\h+(?:\{[^\}]*[\h\{,:]{}[\h\},][^\}]*\}|{})\h*=\h*require\("#;
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        JS_CLASS_PATTERN,
+        JS_FUNCTION_PATTERN,
+        &format!("(?:{IMPORT_PATTERN}|{REQUIRE_PATTERN})"),
+        Box::new(DefaultSmartMode {}),
+    )
+}
@@ -0,0 +1,20 @@
+use super::*;
+
+/// Classes of every kind (data, sealed, enum, etc.) are declared with class, as are objects
+/// (including companion objects, if they're named) and type aliases
+const KOTLIN_CLASS_PATTERN: &str =
+    r#"(?:class|interface|object|typealias) {}\h*(?:[<\(\{:= ]|$)"#;
+
+/// Functions may have type parameters, and extension functions a receiver type before the name
+const KOTLIN_FUNCTION_PATTERN: &str = r#"fun\h+(?:<.*>\h*)?(?:[\w<>\?,\h\.\*]*\.)?{}\h*[<\(]"#;
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        KOTLIN_CLASS_PATTERN,
+        KOTLIN_FUNCTION_PATTERN,
+        IMPORT_PATTERN,
+        Box::new(DefaultSmartMode {}),
+    )
+}
@@ -0,0 +1,19 @@
+use super::*;
+
+/// Classes may be followed by their bases or (since 3.12) type parameters, and types may also be
+/// defined by a type statement
+const PY_CLASS_PATTERN: &str = r#"(?:class {}\h*[\[\(:]|type {}\h*[\[=])"#;
+
+/// Functions may be async, which the pattern doesn't need to see
+const PY_FUNCTION_PATTERN: &str = r#"def {}\h*[\[\(]"#;
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        PY_CLASS_PATTERN,
+        PY_FUNCTION_PATTERN,
+        IMPORT_PATTERN,
+        Box::new(DefaultSmartMode {}),
+    )
+}
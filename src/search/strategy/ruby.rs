@@ -0,0 +1,24 @@
+use super::*;
+
+/// Classes and modules, which may be defined within their namespace by its full name
+const RUBY_CLASS_PATTERN: &str = r#"(?:class|module)\h+(?:[\w:]*::)?{}(?:[\h<;]|$)"#;
+
+/// Methods may be defined on self (or anything else) as well as on instances, by attributes, or
+/// dynamically with define_method
+const RUBY_FUNCTION_PATTERN: &str = {
+    r#"(?:def\h+(?:\w+\.)?{}(?:[\h\(;=]|$)|attr_(?:accessor|reader|writer)[\h\(].*:{}(?:[\h,\)]|$)|define_method[\h\(]\h*(?::|["']){}(?:["'\h,\)]|$))"#
+};
+
+/// Files are required by path, with or without the .rb extension
+const REQUIRE_PATTERN: &str = r#"require(?:_relative)?[\h\(]\h*["'](?:[^"']*/)?{}(?:\.rb)?["']"#;
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        RUBY_CLASS_PATTERN,
+        RUBY_FUNCTION_PATTERN,
+        REQUIRE_PATTERN,
+        Box::new(DefaultSmartMode {}),
+    )
+}
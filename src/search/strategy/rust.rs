@@ -0,0 +1,18 @@
+use super::*;
+
+/// Types are defined by structs, enums, unions, traits and aliases. Inherent impls are included
+/// as they're often where the interesting part of a type is.
+const RUST_CLASS_PATTERN: &str = r#"(?:struct|enum|union|trait|type|impl) {}\h*(?:[<\(\{;: ]|$)"#;
+
+const RUST_FUNCTION_PATTERN: &str = r#"fn {}\h*[<\(]"#;
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        RUST_CLASS_PATTERN,
+        RUST_FUNCTION_PATTERN,
+        IMPORT_PATTERN,
+        Box::new(DefaultSmartMode {}),
+    )
+}
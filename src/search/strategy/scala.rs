@@ -0,0 +1,17 @@
+use super::*;
+
+const SCALA_CLASS_PATTERN: &str =
+    r#"(?:case class|class|trait|object|type|enum) {}\h*(?:[\[\(\{: ]|$)"#;
+
+const SCALA_FUNCTION_PATTERN: &str = r#"def {}[\[\(: ]"#;
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        SCALA_CLASS_PATTERN,
+        SCALA_FUNCTION_PATTERN,
+        IMPORT_PATTERN,
+        Box::new(DefaultSmartMode {}),
+    )
+}
@@ -0,0 +1,179 @@
+use super::*;
+
+use crate::search::dialect::tests::{dialects, grep};
+
+const LANGUAGES: [Language; 12] = [
+    Language::Auto,
+    Language::C,
+    Language::Cpp,
+    Language::Go,
+    Language::Java,
+    Language::Js,
+    Language::Kotlin,
+    Language::Python,
+    Language::Ruby,
+    Language::Rust,
+    Language::Scala,
+    Language::Ts,
+];
+
+const MODES: [SearchMode; 6] = [
+    SearchMode::AllUsage,
+    SearchMode::Class,
+    SearchMode::File,
+    SearchMode::Function,
+    SearchMode::Import,
+    SearchMode::Smart,
+];
+
+#[test]
+/// Every pattern every strategy can produce should be accepted by the engine for every dialect
+fn patterns_compile_in_all_dialects() {
+    let dialects = dialects();
+
+    for lang in &LANGUAGES {
+        let strategy = get_strategy(lang);
+
+        for mode in &MODES {
+            for term in ["Foo", "foo", "foo.bar"] {
+                for dialect in dialects.iter().copied() {
+                    let pattern = strategy.get_pattern(mode, term, dialect);
+
+                    assert!(
+                        grep(dialect, &pattern, "").is_some(),
+                        "{lang:?} {mode:?} failed to compile in {dialect:?}: {pattern}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+/// The same definitions should be found whichever dialect the pattern is written in
+fn patterns_match_in_all_dialects() {
+    let strategy = get_strategy(&Language::Auto);
+    let dialects = dialects();
+    let cases = [
+        (
+            SearchMode::Class,
+            "Update",
+            "sealed trait Update extends Thing {",
+            true,
+        ),
+        (
+            SearchMode::Class,
+            "Update",
+            "sealed trait InventoryUpdate {",
+            false,
+        ),
+        (SearchMode::Class, "Cli", "class Cli:", true),
+        (
+            SearchMode::Function,
+            "get_all_games",
+            "    fn get_all_games(&self) -> Vec<u32>",
+            true,
+        ),
+        (
+            SearchMode::Function,
+            "NewCache",
+            "func (c Cache) NewCache() {",
+            true,
+        ),
+        (
+            SearchMode::Import,
+            "Future",
+            "import scala.concurrent.{ExecutionContext, Future}",
+            true,
+        ),
+        (SearchMode::Import, "fmt", r#"import "fmt""#, true),
+        (SearchMode::Import, "fmt", r#"import "fmtx""#, false),
+    ];
+
+    for (mode, term, line, expected) in cases {
+        for dialect in dialects.iter().copied() {
+            let pattern = strategy.get_pattern(&mode, term, dialect);
+
+            assert_eq!(
+                grep(dialect, &pattern, line),
+                Some(expected),
+                "{dialect:?} {pattern} vs {line}"
+            );
+        }
+    }
+}
+
+#[test]
+/// Definition patterns should capture the name of whatever is defined
+fn definition_patterns_capture_names() {
+    let strategy = get_strategy(&Language::Auto);
+    let cases = [
+        (SearchMode::Class, "sealed trait Update extends Thing {", Some("Update")),
+        (SearchMode::Class, "class Cli:", Some("Cli")),
+        (SearchMode::Class, "let class_name = 1;", None),
+        (SearchMode::Function, "    fn get_all_games(&self) -> Vec<u32>", Some("get_all_games")),
+        (SearchMode::Function, "func (c Cache) NewCache() {", Some("NewCache")),
+    ];
+
+    for (mode, line, expected) in cases {
+        let pattern = strategy.get_definition_pattern(&mode, Dialect::Rust);
+        let re = regex::Regex::new(&pattern).unwrap();
+        let actual = re.captures(line).map(|c| c.get(1).unwrap().as_str());
+
+        assert_eq!(actual, expected, "{pattern} vs {line}");
+    }
+}
+
+#[test]
+/// Names should be captured from whichever alternative of a definition pattern matched
+fn definition_patterns_capture_names_ts() {
+    let strategy = get_strategy(&Language::Ts);
+    let cases = [
+        (SearchMode::Class, "export interface User {", Some("User")),
+        (SearchMode::Class, "type Props<T> = {", Some("Props")),
+        (SearchMode::Class, "import type Props from './props';", None),
+        (SearchMode::Function, "declare function hash(s: string): string;", Some("hash")),
+        (SearchMode::Function, "export const render = async (el) => {", Some("render")),
+        (SearchMode::Function, "const Card = ({ title }: Props) => title;", Some("Card")),
+        (SearchMode::Function, "const limit = (a + b) * 2;", None),
+    ];
+
+    for (mode, line, expected) in cases {
+        let pattern = strategy.get_definition_pattern(&mode, Dialect::Rust);
+        let re = regex::Regex::new(&pattern).unwrap();
+        let actual = re
+            .captures(line)
+            .and_then(|c| c.iter().skip(1).flatten().next())
+            .map(|m| m.as_str());
+
+        assert_eq!(actual, expected, "{pattern} vs {line}");
+    }
+}
+
+#[test]
+/// Names should be captured from c and c++ definitions, but not from types or function pointers
+fn definition_patterns_capture_names_c() {
+    let strategy = get_strategy(&Language::Cpp);
+    let cases = [
+        (SearchMode::Class, "typedef int (*handler)(int);", Some("handler")),
+        (SearchMode::Class, "} Vertex;", Some("Vertex")),
+        (SearchMode::Class, "class Circle final : public Shape {", Some("Circle")),
+        (SearchMode::Class, "struct polygon;", None),
+        (SearchMode::Function, "typedef int (*handler)(int);", None),
+        (SearchMode::Function, "std::string Shape::describe() const", Some("describe")),
+        (SearchMode::Function, "    virtual double area() const = 0;", Some("area")),
+        (SearchMode::Function, "    return M_PI * area();", None),
+        (SearchMode::Function, "#define SQUARE(x) ((x) * (x))", Some("SQUARE")),
+    ];
+
+    for (mode, line, expected) in cases {
+        let pattern = strategy.get_definition_pattern(&mode, Dialect::Rust);
+        let re = regex::Regex::new(&pattern).unwrap();
+        let actual = re
+            .captures(line)
+            .and_then(|c| c.iter().skip(1).flatten().next())
+            .map(|m| m.as_str());
+
+        assert_eq!(actual, expected, "{pattern} vs {line}");
+    }
+}
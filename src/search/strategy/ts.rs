@@ -0,0 +1,34 @@
+use super::js::{JS_FUNCTION_PATTERN, REQUIRE_PATTERN};
+use super::*;
+
+/// Besides classes (abstract or not) and enums, typescript defines types with interface, type
+/// aliases and namespaces (or the older internal modules). A type alias needs its = (or type
+/// parameters) so that type-only imports aren't mistaken for one.
+const TS_CLASS_PATTERN: &str =
+    r#"(?:(?:class|interface|enum|namespace|module) {}\h*(?:[<\{: ]|$)|type {}\h*[<=])"#;
+
+/// Functions are very often defined as arrow functions assigned to a constant, possibly with a
+/// type annotation, type parameters or async; declare function is covered by javascript's pattern
+const ARROW_FUNCTION_PATTERN: &str = {
+    r#"(?:const|let|var)\h+{}\h*(?::[^=]*)?=\h*(?:async\h*)?(?:<[^=]*>\h*)?(?:\(.*\)|\w+)\h*(?::.*)?=>"#
+};
+
+/// Both classes and components (as arrow functions) are usually capitalised, so search for both
+struct TsSmartMode {}
+
+impl SmartMode for TsSmartMode {
+    fn get_pattern(&self, _term: &str, class: &str, function: &str) -> String {
+        format!("(?:{class}|{function})")
+    }
+}
+
+pub(super) fn get_strategy() -> SearchStrategy {
+    SearchStrategy::new(
+        "{}",
+        "{}",
+        TS_CLASS_PATTERN,
+        &format!("(?:{JS_FUNCTION_PATTERN}|{ARROW_FUNCTION_PATTERN})"),
+        &format!("(?:{IMPORT_PATTERN}|{REQUIRE_PATTERN})"),
+        Box::new(TsSmartMode {}),
+    )
+}
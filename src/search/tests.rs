@@ -1,6 +1,11 @@
 use super::*;
 
+use std::cell::RefCell;
+use std::rc::Rc;
+
 use crate::args::SearchMode;
+use crate::backend::{Memory, Result as BackendResult};
+use crate::project::Project;
 
 /// Prepend the prefix to the scala sample files for brevity
 fn scala_file(s: &str) -> String {
@@ -25,10 +30,72 @@ fn go_file(s: &str) -> String {
     format!("test/fixtures/go/{}", s)
 }
 
-/// Construct a Search with an Ag configured to ignore /src to avoid self-referential searches
+/// Prepend the prefix to the java sample files for brevity
+fn java_file(s: &str) -> String {
+    format!("test/fixtures/java/src/main/java/com/example/shop/{}", s)
+}
+
+/// Prepend the prefix to the c and c++ sample files for brevity
+fn c_file(s: &str) -> String {
+    format!("test/fixtures/c/{}", s)
+}
+
+/// Prepend the prefix to the ruby sample files for brevity
+fn ruby_file(s: &str) -> String {
+    format!("test/fixtures/ruby/{}", s)
+}
+
+/// Prepend the prefix to the kotlin sample files for brevity
+fn kotlin_file(s: &str) -> String {
+    format!("test/fixtures/kotlin/app/src/main/kotlin/com/example/notes/{}", s)
+}
+
+/// Prepend the prefix to the javascript sample files for brevity
+fn js_file(s: &str) -> String {
+    format!("test/fixtures/js/{}", s)
+}
+
+/// Prepend the prefix to the typescript sample files for brevity
+fn ts_file(s: &str) -> String {
+    format!("test/fixtures/ts/{}", s)
+}
+
+/// A backend recording the patterns it's asked to search for, to tell which searches were made
+struct Recording {
+    backend: Memory,
+    patterns: RefCell<Vec<String>>,
+}
+
+impl SearchBackend for Recording {
+    fn dialect(&self) -> Dialect {
+        self.backend.dialect()
+    }
+
+    fn search(&self, pattern: &str, filenames: bool, langs: &[Language]) -> BackendResult<String> {
+        self.patterns.borrow_mut().push(pattern.to_string());
+        self.backend.search(pattern, filenames, langs)
+    }
+
+    fn read(&self, filename: &str) -> BackendResult<String> {
+        self.backend.read(filename)
+    }
+}
+
+/// Construct a Search over the fixtures, held in memory so that the search is hermetic
 fn searcher(mode: &SearchMode, lang: &Language) -> Search {
-    let ag = Ag::new(vec!["--ignore".to_string(), "/src".to_string()]);
-    Search::new(ag, mode, lang)
+    Search::new(Box::new(Memory::fixtures()), mode, lang)
+}
+
+/// A hit for a definition or import, in a language decided by the filename
+fn hit(term: &str, filename: &str, line: u64, col: u64, text: &str) -> Hit {
+    Hit {
+        term: term.to_string(),
+        filename: filename.to_string(),
+        line: Some(line),
+        col: Some(col),
+        text: text.to_string(),
+        lang: detect_language(filename),
+    }
 }
 
 #[test]
@@ -114,6 +181,24 @@ fn search_scala_import_multi() {
     assert_eq!(actual, expected);
 }
 
+#[test]
+/// Imports split over several lines should be found, carrying the whole statement
+fn search_scala_import_multiline() {
+    let search = searcher(&SearchMode::Import, &Language::Scala);
+    let expected = vec![Hit {
+        term: "StatValue".to_string(),
+        filename: scala_file("updates/stats/StatUpdateResult.scala"),
+        line: Some(5),
+        col: Some(3),
+        text: "import com.xantoria.mmo.common.model.stats.{StatName, StatValue}".to_string(),
+        lang: DetectedLanguage::Scala,
+    }];
+
+    let actual = search.search("StatValue").unwrap();
+
+    assert_eq!(actual, expected);
+}
+
 #[test]
 /// Find definition of the toString def, which is overridden on one of the classes
 fn search_scala_def() {
@@ -251,6 +336,33 @@ fn search_python_import_renamed() {
     assert_eq!(actual, expected);
 }
 
+#[test]
+/// Imports split over several lines should be found, carrying the whole statement, whether
+/// wrapped in parentheses or continued with a backslash
+fn search_python_import_multiline() {
+    let search = searcher(&SearchMode::Import, &Language::Python);
+    let cases = [
+        ("S3Key", 5, 5, "from s3_browser.paths import (S3Bucket as Bucket, S3Key)"),
+        ("S3Bucket", 4, 5, "from s3_browser.paths import (S3Bucket as Bucket, S3Key)"),
+        ("human_size", 8, 5, "from s3_browser.utils import human_size"),
+    ];
+
+    for (term, line, col, text) in cases {
+        let expected = vec![Hit {
+            term: term.to_string(),
+            filename: py_file("multiline.py"),
+            line: Some(line),
+            col: Some(col),
+            text: text.to_string(),
+            lang: DetectedLanguage::Python,
+        }];
+
+        let actual = search.search(term).unwrap();
+
+        assert_eq!(actual, expected, "{term}");
+    }
+}
+
 #[test]
 fn search_python_smart_class() {
     let search = searcher(&SearchMode::Smart, &Language::Python);
@@ -458,6 +570,34 @@ fn search_rust_import_multi() {
     assert_eq!(actual, expected);
 }
 
+#[test]
+/// Use trees split over several lines should be found, carrying the whole statement
+fn search_rust_import_multiline() {
+    let search = searcher(&SearchMode::Import, &Language::Rust);
+    let expected = vec![Hit {
+        term: "SteamUser".to_string(),
+        filename: rust_file("multiline.rs"),
+        line: Some(8),
+        col: Some(5),
+        text: "use crate::models::{library::Library as GameLibrary, user::SteamUser};".to_string(),
+        lang: DetectedLanguage::Rust,
+    }];
+
+    let actual = search.search("SteamUser").unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Lines which only look like they might be part of an import shouldn't be found
+fn search_rust_import_multiline_lookalike() {
+    let search = searcher(&SearchMode::Import, &Language::Rust);
+
+    let actual = search.search("Shelf").unwrap();
+
+    assert_eq!(actual, vec![]);
+}
+
 #[test]
 fn search_rust_smart_trait() {
     let search = searcher(&SearchMode::Smart, &Language::Rust);
@@ -572,43 +712,99 @@ fn search_go_struct_method() {
     assert_eq!(actual, expected);
 }
 
+/// The statement importing the fixtures' cache.go packages, joined onto one line
+const GO_CACHE_IMPORTS: &str = concat!(
+    r#"import ("context" "encoding/json" "fmt" "github.com/redis/go-redis/v9" "#,
+    r#""github.com/giftig/hellgo/models")"#
+);
+
 #[test]
 fn search_go_import_single() {
     let search = searcher(&SearchMode::Import, &Language::Go);
-    let expected = vec![Hit {
-        term: "fmt".to_string(),
-        filename: go_file("cache/single_import.go"),
-        line: Some(3),
-        col: Some(1),
-        text: r#"import "fmt""#.to_string(),
-        lang: DetectedLanguage::Go,
-    }];
+    let expected = vec![
+        Hit {
+            term: "fmt".to_string(),
+            filename: go_file("cache/cache.go"),
+            line: Some(6),
+            col: Some(2),
+            text: GO_CACHE_IMPORTS.to_string(),
+            lang: DetectedLanguage::Go,
+        },
+        Hit {
+            term: "fmt".to_string(),
+            filename: go_file("cache/single_import.go"),
+            line: Some(3),
+            col: Some(1),
+            text: r#"import "fmt""#.to_string(),
+            lang: DetectedLanguage::Go,
+        },
+    ];
 
     let actual = search.search("fmt").unwrap();
 
     assert_eq!(actual, expected);
 }
 
-// FIXME: Multi-line imports aren't working in any language but they're particularly noticeable
-// in go; will need to adjust regex line-scanning approach to make them work
-#[ignore]
 #[test]
+/// Packages in an import block should be found, carrying the whole block
 fn search_go_import_multi() {
     let search = searcher(&SearchMode::Import, &Language::Go);
-    let expected = vec![Hit {
-        term: "models".to_string(),
-        filename: go_file("cache/cache.go"),
-        line: Some(10),
-        col: Some(3),
-        text: "???".to_string(),
-        lang: DetectedLanguage::Go,
-    }];
+    let expected = vec![
+        Hit {
+            term: "models".to_string(),
+            filename: go_file("cache/cache.go"),
+            line: Some(10),
+            col: Some(2),
+            text: GO_CACHE_IMPORTS.to_string(),
+            lang: DetectedLanguage::Go,
+        },
+        Hit {
+            term: "models".to_string(),
+            filename: go_file("cache/cache_test.go"),
+            line: Some(6),
+            col: Some(2),
+            text: r#"import ("testing" "github.com/giftig/hellgo/models" "github.com/stretchr/testify/assert")"#
+                .to_string(),
+            lang: DetectedLanguage::Go,
+        },
+        Hit {
+            term: "models".to_string(),
+            filename: go_file("cache/config.go"),
+            line: Some(8),
+            col: Some(2),
+            text: r#"import ("os" _ "github.com/lib/pq" . "github.com/onsi/gomega" hm "github.com/giftig/hellgo/models" "gopkg.in/yaml.v3")"#
+                .to_string(),
+            lang: DetectedLanguage::Go,
+        },
+    ];
 
     let actual = search.search("models").unwrap();
 
     assert_eq!(actual, expected);
 }
 
+#[test]
+/// Package names are the last element of the path, give or take a go- prefix and a version,
+/// and packages can also be found by an alias given to them
+fn search_go_import_names() {
+    let search = searcher(&SearchMode::Import, &Language::Go);
+    let cases = [
+        ("redis", "cache/cache.go", 8),
+        ("yaml", "cache/config.go", 9),
+        ("hm", "cache/config.go", 8),
+        ("pq", "cache/config.go", 6),
+        ("gomega", "cache/config.go", 7),
+    ];
+
+    for (term, filename, line) in cases {
+        let actual = search.search(term).unwrap();
+
+        assert_eq!(actual.len(), 1, "{term}");
+        assert_eq!(actual[0].filename, go_file(filename), "{term}");
+        assert_eq!(actual[0].line, Some(line), "{term}");
+    }
+}
+
 #[test]
 fn search_go_smart_func() {
     let search = searcher(&SearchMode::Smart, &Language::Go);
@@ -683,3 +879,806 @@ fn search_partial_filename() {
 
     assert_eq!(actual, expected);
 }
+
+#[test]
+/// Colons in filenames shouldn't be mistaken for the line / col separators
+fn search_filename_with_colons() {
+    let backend = Memory::new(&[("src/a:b/c:12:3.py", "class Cli:\n    pass\n")]);
+    let search = Search::new(Box::new(backend), &SearchMode::Class, &Language::Python);
+    let expected = vec![Hit {
+        term: "Cli".to_string(),
+        filename: "src/a:b/c:12:3.py".to_string(),
+        line: Some(1),
+        col: Some(1),
+        text: "class Cli:".to_string(),
+        lang: DetectedLanguage::Python,
+    }];
+
+    let actual = search.search("Cli").unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// git grep -z separates line and col with NULs rather than colons
+fn parse_hit_nul_separated() {
+    let actual = Hit::parse("a:b.rs\x0012\x003\x00fn foo() {", "foo").unwrap();
+    let expected = Hit {
+        term: "foo".to_string(),
+        filename: "a:b.rs".to_string(),
+        line: Some(12),
+        col: Some(3),
+        text: "fn foo() {".to_string(),
+        lang: DetectedLanguage::Rust,
+    };
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Lines missing the filename terminator or any of the coordinates should be errors, not panics
+fn parse_hit_malformed() {
+    for line in ["foo.rs:12:3:fn foo() {", "foo.rs\x0012", "\x0012:3:fn foo() {"] {
+        let actual = Hit::parse(line, "foo").unwrap_err();
+
+        assert!(matches!(actual, SearchError::HitFragmentCount(_)), "{line:?}");
+    }
+
+    let actual = Hit::parse("foo.rs\x00twelve:3:fn foo() {", "foo").unwrap_err();
+    assert!(matches!(actual, SearchError::HitParseError(_)));
+}
+
+#[test]
+/// Find es module imports, including those split over several lines, and commonjs requires
+fn search_js_import() {
+    let search = searcher(&SearchMode::Import, &Language::Js);
+    let expected = vec![
+        Hit {
+            term: "Button".to_string(),
+            filename: js_file("lib/server.js"),
+            line: Some(3),
+            col: Some(1),
+            text: "const { Button } = require('../src/components/Button')".to_string(),
+            lang: DetectedLanguage::Js,
+        },
+        Hit {
+            term: "Button".to_string(),
+            filename: js_file("src/app.js"),
+            line: Some(4),
+            col: Some(3),
+            text: "import {Button, ButtonGroup} from './components/Button';".to_string(),
+            lang: DetectedLanguage::Js,
+        },
+    ];
+
+    let actual = search.search("Button").unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Find renamed requires by their new name
+fn search_js_import_require_renamed() {
+    let search = searcher(&SearchMode::Import, &Language::Js);
+    let expected = vec![Hit {
+        term: "save".to_string(),
+        filename: js_file("lib/server.js"),
+        line: Some(2),
+        col: Some(1),
+        text: r#"const { readFile, writeFile: save } = require("fs");"#.to_string(),
+        lang: DetectedLanguage::Js,
+    }];
+
+    let actual = search.search("save").unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Interfaces, type aliases, enums, abstract classes and namespaces all define types
+fn search_ts_class() {
+    let search = searcher(&SearchMode::Class, &Language::Ts);
+    let cases = [
+        ("User", 1, 8, "export interface User {"),
+        ("UserId", 6, 8, r#"export type UserId = User["id"];"#),
+        ("Role", 8, 14, "export const enum Role {"),
+        ("Repository", 13, 17, "export abstract class Repository<T> {"),
+        ("Users", 17, 8, "export namespace Users {"),
+    ];
+
+    for (term, line, col, text) in cases {
+        let expected = vec![hit(term, &ts_file("src/models/user.ts"), line, col, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+}
+
+#[test]
+/// Declared functions and arrow functions assigned to constants are functions too
+fn search_ts_function() {
+    let search = searcher(&SearchMode::Function, &Language::Ts);
+    let cases = [
+        (
+            "hashPassword",
+            "src/models/user.ts",
+            21,
+            9,
+            "declare function hashPassword(password: string): string;",
+        ),
+        (
+            "formatUser",
+            "src/models/user.ts",
+            23,
+            8,
+            "export const formatUser = (user: User): string => `${user.name} (${user.id})`;",
+        ),
+        (
+            "fetchUser",
+            "src/models/user.ts",
+            25,
+            8,
+            "export const fetchUser = async <T extends User>(id: UserId): Promise<T> => {",
+        ),
+        (
+            "canEdit",
+            "src/app.tsx",
+            6,
+            8,
+            "export function canEdit(role: Role, id: UserId): boolean {",
+        ),
+    ];
+
+    for (term, filename, line, col, text) in cases {
+        let expected = vec![hit(term, &ts_file(filename), line, col, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+}
+
+#[test]
+/// Components are capitalised arrow functions, so smart mode should find them as well as types
+fn search_ts_smart() {
+    let search = searcher(&SearchMode::Smart, &Language::Ts);
+    let expected = vec![hit(
+        "UserCard",
+        &ts_file("src/app.tsx"),
+        4,
+        8,
+        "export const UserCard = ({ user }: { user: User }) => <p>{formatUser(user)}</p>;",
+    )];
+
+    assert_eq!(search.search("UserCard").unwrap(), expected);
+    assert_eq!(search.search("Role").unwrap().len(), 1);
+}
+
+#[test]
+/// Type-only imports are imports too
+fn search_ts_import_type() {
+    let search = searcher(&SearchMode::Import, &Language::Ts);
+    let expected = vec![hit(
+        "UserId",
+        &ts_file("src/app.tsx"),
+        1,
+        1,
+        r#"import type { User, UserId } from "./models/user";"#,
+    )];
+
+    assert_eq!(search.search("UserId").unwrap(), expected);
+}
+
+#[test]
+/// Without a language hint, each file should be searched with the patterns for its own language:
+/// go's type Point struct in a python comment is not a definition
+fn search_auto_class_by_language() {
+    let search = searcher(&SearchMode::Class, &Language::Auto);
+    let expected = vec![Hit {
+        term: "Point".to_string(),
+        filename: py_file("shapes.py"),
+        line: Some(7),
+        col: Some(1),
+        text: "class Point:".to_string(),
+        lang: DetectedLanguage::Python,
+    }];
+
+    let actual = search.search("Point").unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Definitions particular to one language should still be found without a language hint
+fn search_auto_language_specific() {
+    let search = searcher(&SearchMode::Smart, &Language::Auto);
+
+    let actual = search.search("UserCard").unwrap();
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].lang, DetectedLanguage::Ts);
+
+    let actual = search.search("Vector").unwrap();
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].text, "type Vector = list[float]");
+}
+
+#[test]
+/// An auto search restricted to the project's languages shouldn't look at any other files
+fn search_auto_project_languages() {
+    let search = searcher(&SearchMode::Class, &Language::Auto)
+        .with_languages(vec![Language::Go, Language::Rust]);
+
+    assert_eq!(search.search("Point").unwrap(), vec![]);
+
+    let search = searcher(&SearchMode::Class, &Language::Auto)
+        .with_languages(vec![Language::Go, Language::Python]);
+
+    assert_eq!(search.search("Point").unwrap().len(), 1);
+}
+
+#[test]
+/// A project's languages shouldn't stop files and usages being found in files of other kinds
+fn search_project_anywhere() {
+    let backend = Memory::new(&[
+        ("README.md", "Run `steam` to get started\n"),
+        ("src/main.rs", "fn main() {\n    steam();\n}\n"),
+    ]);
+    let backend = Rc::new(backend);
+    let project = Project { languages: vec![Language::Rust] };
+    let lang = project.language(None);
+
+    let search = |mode| {
+        Search::new(Box::new(backend.clone()), &mode, &lang)
+            .with_project(project.clone())
+            .with_languages(project.search_languages(&mode, None))
+    };
+
+    let files = search(SearchMode::File).search("README").unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].filename, "README.md");
+
+    let usages = search(SearchMode::AllUsage).search("steam").unwrap();
+    let filenames: Vec<&str> = usages.iter().map(|h| h.filename.as_str()).collect();
+    assert_eq!(filenames, vec!["README.md", "src/main.rs"]);
+}
+
+#[test]
+/// Classes, interfaces, enums, records and annotation types all define types
+fn search_java_class() {
+    let search = searcher(&SearchMode::Class, &Language::Java);
+    let cases = [
+        ("Order", "model/Order.java", 7, 8, "public class Order {"),
+        ("Status", "model/Status.java", 3, 8, "public enum Status {"),
+        ("Audited", "model/Audited.java", 7, 8, "public @interface Audited {"),
+        (
+            "Money",
+            "model/Money.java",
+            5,
+            8,
+            "public record Money(BigDecimal amount, String currency) {",
+        ),
+        (
+            "LineItem",
+            "model/Order.java",
+            23,
+            12,
+            "    public record LineItem(String sku, BigDecimal price) {}",
+        ),
+        (
+            "OrderRepository",
+            "service/OrderRepository.java",
+            7,
+            8,
+            "public interface OrderRepository {",
+        ),
+    ];
+
+    for (term, filename, line, col, text) in cases {
+        let expected = vec![hit(term, &java_file(filename), line, col, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+}
+
+#[test]
+/// Methods should be found whatever their modifiers and return type, but calls should not
+fn search_java_method() {
+    let search = searcher(&SearchMode::Function, &Language::Java);
+    let cases = [
+        ("total", "model/Order.java", 15, 12, "    public BigDecimal total() {"),
+        (
+            "firstOf",
+            "model/Order.java",
+            19,
+            37,
+            "    public static <T extends Order> Optional<T> firstOf(List<T> orders) {",
+        ),
+        ("value", "model/Audited.java", 8, 5, r#"    String value() default "";"#),
+        (
+            "findByStatus",
+            "service/OrderRepository.java",
+            8,
+            5,
+            "    List<Order> findByStatus(Status status);",
+        ),
+    ];
+
+    for (term, filename, line, col, text) in cases {
+        let expected = vec![hit(term, &java_file(filename), line, col, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+
+    assert_eq!(search.search("requireNonNull").unwrap(), vec![]);
+}
+
+#[test]
+/// Lines continuing an import should only be looked for if the languages searched can split them
+fn search_import_continuations() {
+    let cases = [
+        (Language::Java, vec![Language::Java], false),
+        (Language::Python, vec![Language::Python], true),
+        (Language::Auto, vec![Language::Java, Language::Kotlin], false),
+        (Language::Auto, vec![Language::Java, Language::Go], true),
+        (Language::Auto, vec![Language::Auto], true),
+        (Language::Auto, vec![], true),
+    ];
+
+    for (lang, languages, expected) in cases {
+        let backend = Rc::new(Recording {
+            backend: Memory::fixtures(),
+            patterns: RefCell::new(vec![]),
+        });
+        let search = Search::new(Box::new(backend.clone()), &SearchMode::Import, &lang)
+            .with_languages(languages.clone());
+        search.search("Status").unwrap();
+
+        let continuation = backend.dialect().build(&continuation_pattern(), "Status");
+        let actual = backend.patterns.borrow().contains(&continuation);
+        assert_eq!(actual, expected, "{lang:?} {languages:?}");
+    }
+}
+
+#[test]
+fn search_java_import() {
+    let search = searcher(&SearchMode::Import, &Language::Java);
+
+    let import = "import com.example.shop.model.Status;";
+    let expected = vec![
+        hit("Status", &java_file("service/OrderRepository.java"), 4, 1, import),
+        hit("Status", &java_file("service/OrderService.java"), 7, 1, import),
+    ];
+    assert_eq!(search.search("Status").unwrap(), expected);
+
+    let expected = vec![hit(
+        "requireNonNull",
+        &java_file("service/OrderService.java"),
+        3,
+        1,
+        "import static java.util.Objects.requireNonNull;",
+    )];
+    assert_eq!(search.search("requireNonNull").unwrap(), expected);
+}
+
+#[test]
+/// A class nothing imports yet should be imported from the package of the file defining it
+fn search_java_import_from_package() {
+    let search = searcher(&SearchMode::Import, &Language::Java);
+    let expected = vec![hit(
+        "Money",
+        &java_file("model/Money.java"),
+        1,
+        1,
+        "package com.example.shop.model;",
+    )];
+
+    assert_eq!(search.search("Money").unwrap(), expected);
+
+    // Nested classes can't be imported by their package alone
+    assert_eq!(search.search("LineItem").unwrap(), vec![]);
+}
+
+#[test]
+/// Smart mode should use the language's own patterns for classes and functions
+fn search_java_smart() {
+    let search = searcher(&SearchMode::Smart, &Language::Java);
+
+    let actual = search.search("firstOf").unwrap();
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].line, Some(19));
+
+    let actual = search.search("Audited").unwrap();
+    assert_eq!(actual.len(), 1);
+    assert_eq!(actual[0].text, "public @interface Audited {");
+}
+
+#[test]
+/// Classes of every kind, objects and type aliases all define types
+fn search_kotlin_class() {
+    let search = searcher(&SearchMode::Class, &Language::Kotlin);
+    let cases = [
+        (
+            "Note",
+            "model/Note.kt",
+            7,
+            6,
+            "data class Note(val id: NoteId, val title: String, val created: Instant)",
+        ),
+        ("NoteId", "model/Note.kt", 5, 1, "typealias NoteId = Long"),
+        ("NoteEvent", "model/Note.kt", 9, 8, "sealed interface NoteEvent {"),
+        (
+            "Created",
+            "model/Note.kt",
+            10,
+            10,
+            "    data class Created(val note: Note) : NoteEvent",
+        ),
+        ("Colour", "model/Note.kt", 14, 6, "enum class Colour { RED, GREEN, BLUE }"),
+        ("NoteFormatter", "ui/NoteFormatter.kt", 7, 1, "object NoteFormatter {"),
+        ("Factory", "ui/NotesViewModel.kt", 16, 15, "    companion object Factory {"),
+    ];
+
+    for (term, filename, line, col, text) in cases {
+        let expected = vec![hit(term, &kotlin_file(filename), line, col, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+}
+
+#[test]
+/// Functions should be found with type parameters and receivers, but not where they're called
+fn search_kotlin_function() {
+    let search = searcher(&SearchMode::Function, &Language::Kotlin);
+    let cases = [
+        (
+            "format",
+            "ui/NoteFormatter.kt",
+            10,
+            5,
+            r#"    fun format(note: Note): String = "${note.title} (${dates.format(note.created)})""#,
+        ),
+        (
+            "summary",
+            "ui/NoteFormatter.kt",
+            13,
+            1,
+            "fun Note.summary(length: Int = 20): String = title.take(length)",
+        ),
+        (
+            "secondOrNull",
+            "ui/NoteFormatter.kt",
+            15,
+            1,
+            "fun <T> List<T>.secondOrNull(): T? = getOrNull(1)",
+        ),
+        (
+            "create",
+            "ui/NotesViewModel.kt",
+            17,
+            9,
+            "        fun create(): NotesViewModel = NotesViewModel()",
+        ),
+    ];
+
+    for (term, filename, line, col, text) in cases {
+        let expected = vec![hit(term, &kotlin_file(filename), line, col, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+
+    assert_eq!(search.search("take").unwrap(), vec![]);
+}
+
+#[test]
+/// Renamed imports should be found by either name
+fn search_kotlin_import_renamed() {
+    let search = searcher(&SearchMode::Import, &Language::Kotlin);
+    let import = "import com.example.notes.model.NoteId as Id";
+
+    for term in ["NoteId", "Id"] {
+        let expected = vec![hit(term, &kotlin_file("ui/NoteFormatter.kt"), 4, 1, import)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+}
+
+#[test]
+/// Top level classes and functions nothing imports yet are imported from their package
+fn search_kotlin_import_from_package() {
+    let search = searcher(&SearchMode::Import, &Language::Kotlin);
+    let cases = [
+        ("Colour", "model/Note.kt", "package com.example.notes.model"),
+        ("summary", "ui/NoteFormatter.kt", "package com.example.notes.ui"),
+    ];
+
+    for (term, filename, text) in cases {
+        let expected = vec![hit(term, &kotlin_file(filename), 1, 1, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+
+    assert_eq!(search.search("create").unwrap(), vec![]);
+}
+
+#[test]
+/// Types are defined in many ways in c, including typedefs and macros, but are only found where
+/// they're defined
+fn search_c_class() {
+    let search = searcher(&SearchMode::Class, &Language::C);
+    let cases = [
+        ("Vertex", 12, "} Vertex;"),
+        ("vertex_count_t", 14, "typedef unsigned long vertex_count_t;"),
+        (
+            "vertex_visitor",
+            16,
+            "typedef int (*vertex_visitor)(const Vertex *p, void *ctx);",
+        ),
+        ("polygon", 18, "struct polygon {"),
+        ("winding", 23, "enum winding { CLOCKWISE, ANTICLOCKWISE };"),
+        ("number", 25, "union number {"),
+        ("MAX_VERTICES", 6, "#define MAX_VERTICES 64"),
+    ];
+
+    for (term, line, text) in cases {
+        let expected = vec![hit(term, &c_file("include/geometry.h"), line, 1, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+}
+
+#[test]
+fn search_cpp_class() {
+    let search = searcher(&SearchMode::Class, &Language::Cpp);
+    let cases = [
+        ("Shape", 9, "class Shape {"),
+        ("Circle", 16, "class Circle final : public Shape {"),
+        ("Unit", 25, "enum class Unit { Metres, Feet };"),
+        ("ShapeList", 27, "using ShapeList = std::vector<std::unique_ptr<Shape>>;"),
+    ];
+
+    for (term, line, text) in cases {
+        let expected = vec![hit(term, &c_file("src/shapes/shape.hpp"), line, 1, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+}
+
+#[test]
+/// Functions should be found where they're declared in headers as well as where they're defined,
+/// with their return type on the line above or not, but not where they're called
+fn search_c_function() {
+    let search = searcher(&SearchMode::Function, &Language::C);
+
+    assert_eq!(
+        search.search("polygon_visit").unwrap(),
+        vec![
+            hit(
+                "polygon_visit",
+                &c_file("include/geometry.h"),
+                31,
+                1,
+                "int polygon_visit(const struct polygon *poly, vertex_visitor visit, void *ctx);",
+            ),
+            hit(
+                "polygon_visit",
+                &c_file("src/geometry.c"),
+                18,
+                1,
+                "int polygon_visit(const struct polygon *poly, vertex_visitor visit, void *ctx)",
+            ),
+        ]
+    );
+    assert_eq!(
+        search.search("print_vertex").unwrap(),
+        vec![hit(
+            "print_vertex",
+            &c_file("src/geometry.c"),
+            12,
+            1,
+            "print_vertex(const Vertex *p, void *ctx)"
+        )]
+    );
+    assert_eq!(
+        search.search("SQUARE").unwrap(),
+        vec![hit("SQUARE", &c_file("include/geometry.h"), 7, 1, "#define SQUARE(x) ((x) * (x))")]
+    );
+    assert_eq!(search.search("sqrt").unwrap(), vec![]);
+}
+
+#[test]
+/// Methods should be found where they're declared in a class and where they're defined outside
+/// it, qualified by the class name
+fn search_cpp_function() {
+    let search = searcher(&SearchMode::Function, &Language::Cpp);
+
+    let (source, header) = (c_file("src/shapes/shape.cpp"), c_file("src/shapes/shape.hpp"));
+    assert_eq!(
+        search.search("area").unwrap(),
+        vec![
+            hit("area", &source, 17, 1, "double Circle::area() const"),
+            hit("area", &header, 12, 5, "    virtual double area() const = 0;"),
+            hit("area", &header, 19, 5, "    double area() const override;"),
+        ]
+    );
+    assert_eq!(
+        search.search("Circle").unwrap(),
+        vec![hit(
+            "Circle",
+            &c_file("src/shapes/shape.cpp"),
+            15,
+            1,
+            "Circle::Circle(double radius) : radius_(radius) {}"
+        )]
+    );
+}
+
+#[test]
+/// Headers named after the term should be found wherever they're included
+fn search_cpp_include() {
+    let search = searcher(&SearchMode::Import, &Language::Cpp);
+
+    assert_eq!(
+        search.search("memory").unwrap(),
+        vec![
+            hit("memory", &c_file("src/main.cpp"), 2, 1, "#include <memory>"),
+            hit("memory", &c_file("src/shapes/shape.hpp"), 3, 1, "#include <memory>"),
+        ]
+    );
+    assert_eq!(
+        search.search("shape").unwrap(),
+        vec![
+            hit("shape", &c_file("src/main.cpp"), 4, 1, r#"#include "shapes/shape.hpp""#),
+            hit("shape", &c_file("src/shapes/shape.cpp"), 1, 1, r#"#include "shape.hpp""#),
+        ]
+    );
+
+    // Names are case sensitive, so the class is included from its definition instead
+    assert_eq!(
+        search.search("Shape").unwrap(),
+        vec![hit("Shape", &c_file("src/shapes/shape.hpp"), 9, 1, "class Shape {")]
+    );
+}
+
+#[test]
+/// Definitions nothing includes by name are included from the header declaring them
+fn search_c_include_from_header() {
+    let search = searcher(&SearchMode::Import, &Language::Auto);
+    let cases = [
+        (
+            "distance",
+            "include/geometry.h",
+            30,
+            "double distance(const Vertex *a, const Vertex *b);",
+        ),
+        ("Vertex", "include/geometry.h", 12, "} Vertex;"),
+        ("Circle", "src/shapes/shape.hpp", 16, "class Circle final : public Shape {"),
+    ];
+
+    for (term, filename, line, text) in cases {
+        let expected = vec![hit(term, &c_file(filename), line, 1, text)];
+
+        assert_eq!(search.search(term).unwrap(), expected, "{term}");
+    }
+
+    assert_eq!(search.search("print_vertex").unwrap(), vec![]);
+}
+
+#[test]
+/// Classes and modules should be found whether they're nested in their namespace or not
+fn search_ruby_class() {
+    let search = searcher(&SearchMode::Class, &Language::Ruby);
+    let sync = "app/services/billing/invoice_sync.rb";
+
+    assert_eq!(
+        search.search("InvoiceSync").unwrap(),
+        vec![hit("InvoiceSync", &ruby_file(sync), 4, 3, "  class InvoiceSync")]
+    );
+    assert_eq!(
+        search.search("Account").unwrap(),
+        vec![hit(
+            "Account",
+            &ruby_file("app/models/billing/account.rb"),
+            3,
+            1,
+            "class Billing::Account < ApplicationRecord"
+        )]
+    );
+    assert_eq!(
+        search.search("Billing").unwrap(),
+        vec![hit("Billing", &ruby_file(sync), 3, 1, "module Billing")]
+    );
+}
+
+#[test]
+/// Constants should be found in the file zeitwerk would load them from, by their full name, even
+/// where that name appears nowhere in the file
+fn search_ruby_class_by_path() {
+    let cases = [
+        (
+            "Billing::InvoiceSync",
+            "app/services/billing/invoice_sync.rb",
+            4,
+            3,
+            "  class InvoiceSync",
+        ),
+        (
+            "Billing::Account",
+            "app/models/billing/account.rb",
+            3,
+            1,
+            "class Billing::Account < ApplicationRecord",
+        ),
+        (
+            "Billing::Status",
+            "app/models/billing/status.rb",
+            3,
+            10,
+            "Billing::Status = Struct.new(:code, :label)",
+        ),
+    ];
+
+    let project = Project { languages: vec![Language::Ruby] };
+
+    for lang in [Language::Ruby, Language::Auto] {
+        for mode in [SearchMode::Class, SearchMode::Smart] {
+            let search = searcher(&mode, &lang).with_project(project.clone());
+
+            for (term, filename, line, col, text) in cases {
+                let expected = vec![hit(term, &ruby_file(filename), line, col, text)];
+
+                assert_eq!(search.search(term).unwrap(), expected, "{term} {lang:?} {mode:?}");
+            }
+        }
+    }
+
+    let search = searcher(&SearchMode::Class, &Language::Ruby);
+    assert_eq!(search.search("Billing::Ledger").unwrap(), vec![]);
+}
+
+#[test]
+/// Files are only looked up by constant for ruby, so other projects aren't searched for them
+fn search_ruby_class_by_path_other_projects() {
+    let project = Project { languages: vec![Language::Python] };
+
+    for search in [
+        searcher(&SearchMode::Class, &Language::Auto),
+        searcher(&SearchMode::Class, &Language::Auto).with_project(project),
+        searcher(&SearchMode::Class, &Language::Python),
+    ] {
+        assert_eq!(search.search("Billing::Status").unwrap(), vec![]);
+    }
+}
+
+#[test]
+/// Methods are defined on instances and classes, by attributes and dynamically
+fn search_ruby_function() {
+    let search = searcher(&SearchMode::Function, &Language::Ruby);
+    let sync = "app/services/billing/invoice_sync.rb";
+    let account = "app/models/billing/account.rb";
+
+    assert_eq!(
+        search.search("call").unwrap(),
+        vec![
+            hit("call", &ruby_file(sync), 7, 5, "    def self.call(account, since: nil)"),
+            hit("call", &ruby_file(sync), 16, 5, "    def call"),
+        ]
+    );
+    assert_eq!(
+        search.search("since").unwrap(),
+        vec![hit("since", &ruby_file(sync), 5, 5, "    attr_reader :account, :since")]
+    );
+    assert_eq!(
+        search.search("balance").unwrap(),
+        vec![hit("balance", &ruby_file(account), 6, 3, "  attr_accessor :balance, :currency")]
+    );
+    assert_eq!(
+        search.search("overdue?").unwrap(),
+        vec![hit("overdue?", &ruby_file(account), 8, 3, "  define_method(:overdue?) do")]
+    );
+    assert_eq!(search.search("record").unwrap(), vec![]);
+}
+
+#[test]
+fn search_ruby_require() {
+    let search = searcher(&SearchMode::Import, &Language::Ruby);
+    let expected = vec![hit("csv", &ruby_file("lib/tasks/export.rake"), 1, 1, r#"require "csv""#)];
+
+    assert_eq!(search.search("csv").unwrap(), expected);
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod tests;
+
+use regex::Regex;
+
+/// Convert a camel-cased constant name to the snake case used for its file, as rails does, so
+/// that HTMLParser is html_parser and InvoiceSync is invoice_sync
+fn underscore(name: &str) -> String {
+    let acronym = Regex::new(r"([A-Z\d]+)([A-Z][a-z])").unwrap();
+    let word = Regex::new(r"([a-z\d])([A-Z])").unwrap();
+
+    let name = acronym.replace_all(name, "${1}_${2}");
+    word.replace_all(&name, "${1}_${2}").to_lowercase()
+}
+
+/// The path a ruby constant is autoloaded from by zeitwerk, relative to whichever root directory
+/// it's in (e.g. app/models): each namespace is a directory, e.g. billing/invoice_sync.rb for
+/// Billing::InvoiceSync. Anything which isn't a constant has no path.
+pub(crate) fn constant_path(term: &str) -> Option<String> {
+    let constant = Regex::new(r"^[A-Z]\w*$").unwrap();
+    let names: Vec<&str> = term.trim_start_matches("::").split("::").collect();
+
+    if !names.iter().all(|n| constant.is_match(n)) {
+        return None;
+    }
+
+    let path: Vec<String> = names.into_iter().map(underscore).collect();
+
+    Some(format!("{}.rb", path.join("/")))
+}
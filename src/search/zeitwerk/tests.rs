@@ -0,0 +1,20 @@
+use super::*;
+
+#[test]
+fn constant_paths() {
+    let cases = [
+        ("InvoiceSync", Some("invoice_sync.rb")),
+        ("Billing::InvoiceSync", Some("billing/invoice_sync.rb")),
+        ("::Billing::Account", Some("billing/account.rb")),
+        ("HTMLParser", Some("html_parser.rb")),
+        ("OAuth2Client", Some("o_auth2_client.rb")),
+        ("Api::V2::Users", Some("api/v2/users.rb")),
+        ("invoice_sync", None),
+        ("Billing::sync", None),
+        ("Billing.sync", None),
+    ];
+
+    for (term, expected) in cases {
+        assert_eq!(constant_path(term).as_deref(), expected, "{term}");
+    }
+}
@@ -0,0 +1,254 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::args::{validate_style, Args, Language, OutputStyle, SearchMode};
+use crate::backend::SearchBackend;
+use crate::fmt::HitFormatter;
+use crate::index::symbols::SymbolCache;
+use crate::index::IndexCache;
+use crate::project::Project;
+use crate::search::Search;
+
+// Standard JSON-RPC error codes, plus our own for searches which fail
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SEARCH_FAILED: i64 = -32000;
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new<S: ToString>(code: i64, message: S) -> RpcError {
+        RpcError {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+impl Response {
+    fn new(id: Value, res: std::result::Result<Value, RpcError>) -> Response {
+        let (result, error) = match res {
+            Ok(v) => (Some(v), None),
+            Err(e) => (None, Some(e)),
+        };
+
+        Response {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+/// Parameters for the search method; anything omitted falls back to the options the server was
+/// started with
+#[derive(Deserialize)]
+struct SearchParams {
+    term: String,
+    mode: Option<SearchMode>,
+    lang: Option<Language>,
+    output_style: Option<OutputStyle>,
+    #[serde(default)]
+    first_hit: bool,
+    target_file: Option<PathBuf>,
+}
+
+/// Answers search requests for the lifetime of an editor session. A single backend, and with it
+/// any files it keeps warm, is shared between requests, as are any loaded import indexes, the
+/// project's symbol index and the search set up for each mode and language asked for.
+pub struct Server {
+    backend: Rc<dyn SearchBackend>,
+    mode: SearchMode,
+    lang: Option<Language>,
+    project: Project,
+    output_style: OutputStyle,
+    use_import_index_file: bool,
+    indexes: IndexCache,
+    symbols: SymbolCache,
+    searches: HashMap<(SearchMode, Option<Language>), Search>,
+}
+
+impl Server {
+    pub fn new(backend: Box<dyn SearchBackend>, args: &Args) -> Server {
+        Server {
+            backend: Rc::from(backend),
+            mode: args.mode.clone(),
+            lang: args.lang.clone(),
+            project: Project::default(),
+            output_style: args.output_style.clone(),
+            use_import_index_file: args.use_import_index_file,
+            indexes: IndexCache::new(
+                &args.import_index_file,
+                &args.import_index_layers,
+                &args.no_bundled_index,
+            ),
+            symbols: SymbolCache::default(),
+            searches: HashMap::new(),
+        }
+    }
+
+    /// Search the given project, whose language is used when a search doesn't give one
+    pub fn with_project(mut self, project: Project) -> Server {
+        self.project = project;
+        self
+    }
+
+    /// Answer definition searches from the project's symbol index
+    pub fn with_symbols(mut self, symbols: SymbolCache) -> Server {
+        self.symbols = symbols;
+        self.searches.clear();
+        self
+    }
+
+    /// Handle requests, one JSON object per line, until the input ends or we're asked to stop
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (response, done) = self.handle(&line);
+            writeln!(output, "{}", serde_json::to_string(&response)?)?;
+            output.flush()?;
+
+            if done {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Produce a response to a single request, and whether the server should now stop
+    fn handle(&mut self, line: &str) -> (Response, bool) {
+        let req: Request = match serde_json::from_str(line) {
+            Ok(r) => r,
+            Err(e) => {
+                return (
+                    Response::new(Value::Null, Err(RpcError::new(PARSE_ERROR, e))),
+                    false,
+                )
+            }
+        };
+
+        let res = match req.method.as_str() {
+            "search" => serde_json::from_value(req.params)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, e))
+                .and_then(|params| self.search(params)),
+            "shutdown" => return (Response::new(req.id, Ok(Value::Null)), true),
+            m => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method: {m}"),
+            )),
+        };
+
+        (Response::new(req.id, res), false)
+    }
+
+    /// Run a search, providing both the structured hits and the lines qf would have printed
+    fn search(&mut self, params: SearchParams) -> std::result::Result<Value, RpcError> {
+        let mode = params.mode.unwrap_or_else(|| self.mode.clone());
+        let given_lang = params.lang.or_else(|| self.lang.clone());
+        let lang = self.project.language(given_lang.as_ref());
+        let style = params
+            .output_style
+            .unwrap_or_else(|| self.output_style.clone());
+
+        validate_style(&mode, &style).map_err(|e| RpcError::new(INVALID_PARAMS, e))?;
+
+        if let Err(e) = self.symbols.refresh(&mode) {
+            eprintln!("Failed to refresh symbol index: {e}");
+        }
+
+        let (backend, project, symbols) = (&self.backend, &self.project, &self.symbols);
+        let search = self
+            .searches
+            .entry((mode.clone(), given_lang.clone()))
+            .or_insert_with(|| {
+                let backend = symbols.backend(&mode).unwrap_or_else(|| backend.clone());
+
+                Search::new(Box::new(backend), &mode, &lang)
+                    .with_project(project.clone())
+                    .with_languages(project.search_languages(&mode, given_lang.as_ref()))
+            });
+        let hits = search
+            .search(&params.term)
+            .map_err(|e| RpcError::new(SEARCH_FAILED, e))?;
+
+        // Generated imports are ranked across all the hits, so the first is the most common one
+        let shown = if params.first_hit && style != OutputStyle::Import {
+            &hits[..hits.len().min(1)]
+        } else {
+            &hits[..]
+        };
+
+        let formatter =
+            HitFormatter::new(&style, &mode).with_target(params.target_file.as_deref());
+        let mut output = formatter
+            .write_all(shown)
+            .map_err(|e| RpcError::new(SEARCH_FAILED, e))?;
+        if params.first_hit {
+            output.truncate(1);
+        }
+        let records: Vec<_> = shown.iter().map(|h| formatter.record(h)).collect();
+
+        // As on the command line, fall back to the import indexes if the project had nothing to
+        // import, detailing which index each import came from
+        if output.is_empty()
+            && self.use_import_index_file
+            && mode == SearchMode::Import
+            && style == OutputStyle::Import
+        {
+            let mut imports = vec![];
+            for lang in self.project.index_languages(&lang) {
+                let lang_str = format!("{:?}", lang).to_lowercase();
+                let found = self
+                    .indexes
+                    .lookup(&lang_str, &params.term)
+                    .map_err(|e| RpcError::new(SEARCH_FAILED, e))?;
+                imports.extend(found);
+            }
+
+            output.extend(imports.iter().map(|i| i.import.clone()));
+
+            return Ok(json!({ "hits": records, "output": output, "index": imports }));
+        }
+
+        Ok(json!({ "hits": records, "output": output }))
+    }
+}
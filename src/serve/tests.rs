@@ -0,0 +1,249 @@
+use super::*;
+
+use clap::Parser;
+use serde_json::json;
+
+use std::path::Path;
+
+use crate::backend::Memory;
+use crate::index::models::SymbolIndex;
+
+/// A server over the in-memory fixtures, started with the given command line options
+fn server(opts: &[&str]) -> Server {
+    let args = Args::parse_from([&["qf"], opts, &["serve"]].concat());
+    Server::new(Box::new(Memory::fixtures()), &args)
+}
+
+/// Feed each request to the server in turn, returning the parsed responses
+fn exchange(server: &mut Server, requests: &[Value]) -> Vec<Value> {
+    let input: String = requests.iter().map(|r| format!("{r}\n")).collect();
+    let mut output = vec![];
+
+    server.run(input.as_bytes(), &mut output).unwrap();
+
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn serve_search() {
+    let mut server = server(&[]);
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "search",
+        "params": {"term": "Cli", "mode": "class", "lang": "python", "output_style": "quickfix"},
+    });
+
+    let actual = exchange(&mut server, &[request]);
+    let expected = vec![json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "result": {
+            "hits": [{
+                "term": "Cli",
+                "filename": "test/fixtures/python/cli.py",
+                "line": 17,
+                "col": 1,
+                "text": "class Cli:",
+                "lang": "python",
+            }],
+            "output": ["test/fixtures/python/cli.py:17:1:class Cli:"],
+        },
+    })];
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+/// Options given on the command line should apply unless overridden in the request
+fn serve_search_defaults() {
+    let mut server = server(&[
+        "--mode",
+        "import",
+        "--output-style",
+        "import",
+        "--lang",
+        "rust",
+    ]);
+    let requests = [
+        json!({"id": 1, "method": "search", "params": {"term": "HashMap"}}),
+        json!({"id": 2, "method": "search", "params": {"term": "get_all_games", "mode": "function", "output_style": "coords", "first_hit": true}}),
+    ];
+
+    let actual = exchange(&mut server, &requests);
+
+    assert_eq!(
+        actual[0]["result"]["output"],
+        json!(["use std::collections::HashMap;"])
+    );
+    assert_eq!(
+        actual[1]["result"]["output"],
+        json!(["test/fixtures/rust/steam.rs:29:5"])
+    );
+}
+
+#[test]
+/// The import index should be loaded when the project has no answer, and kept for next time
+fn serve_import_index_fallback() {
+    let mut server = server(&[
+        "--mode",
+        "import",
+        "--output-style",
+        "import",
+        "--import-index-file",
+        "test/fixtures/import-index/valid.json",
+    ]);
+    let request =
+        json!({"id": 1, "method": "search", "params": {"term": "PathBuf", "lang": "rust"}});
+
+    let actual = exchange(&mut server, &[request]);
+
+    assert_eq!(
+        actual[0]["result"]["output"],
+        json!(["use std::path::PathBuf"])
+    );
+    assert_eq!(
+        actual[0]["result"]["index"],
+        json!([{
+            "import": "use std::path::PathBuf",
+            "layer": "file",
+            "file": "test/fixtures/import-index/valid.json",
+        }])
+    );
+    assert!(server.indexes.is_loaded("rust"));
+}
+
+#[test]
+/// Without a language in the request or on the command line, the project's language is used, for
+/// both the search and the import index
+fn serve_project_language() {
+    let mut server = server(&[
+        "--import-index-file",
+        "test/fixtures/import-index/valid.json",
+    ])
+    .with_project(Project {
+        languages: vec![Language::Rust],
+    });
+    let requests = [
+        json!({"id": 1, "method": "search", "params": {"term": "Cli", "mode": "class"}}),
+        json!({"id": 2, "method": "search", "params": {"term": "PathBuf", "mode": "import", "output_style": "import"}}),
+    ];
+
+    let actual = exchange(&mut server, &requests);
+
+    assert_eq!(actual[0]["result"]["hits"], json!([]));
+    assert_eq!(
+        actual[1]["result"]["output"],
+        json!(["use std::path::PathBuf"])
+    );
+}
+
+#[test]
+/// Searches should be set up once for each mode and language, then reused for later requests
+fn serve_reuses_searches() {
+    let mut server = server(&["--lang", "python"]);
+    let requests = [
+        json!({"id": 1, "method": "search", "params": {"term": "Cli", "mode": "class"}}),
+        json!({"id": 2, "method": "search", "params": {"term": "Bookmark", "mode": "class"}}),
+        json!({"id": 3, "method": "search", "params": {"term": "Cli", "mode": "class", "lang": "rust"}}),
+        json!({"id": 4, "method": "search", "params": {"term": "Cli", "mode": "class"}}),
+    ];
+
+    let actual = exchange(&mut server, &requests);
+
+    assert_eq!(actual[0]["result"], actual[3]["result"]);
+    assert_eq!(actual[2]["result"]["hits"], json!([]));
+    assert_eq!(server.searches.len(), 2);
+}
+
+#[test]
+/// Definition searches should be answered from the symbol index, if the server was given one
+fn serve_symbol_index() {
+    // The server brings the index up to date with the working directory before each search, so
+    // start it off current and doctor an unmodified file the server won't read again
+    let mut index = SymbolIndex::new(Path::new("/project"));
+    index.refresh(Path::new(".")).unwrap();
+
+    let cli = index.files.get_mut("test/fixtures/python/cli.py").unwrap();
+    for symbol in cli.symbols.iter_mut().filter(|s| s.name == "Cli") {
+        symbol.text = symbol.text.replace("Cli", "Doctored");
+    }
+
+    let mut server = server(&["--lang", "python"]).with_symbols(SymbolCache::new(Some(index)));
+    let requests = [
+        json!({"id": 1, "method": "search", "params": {"term": "Cli", "mode": "class"}}),
+        json!({"id": 2, "method": "search", "params": {"term": "Cli", "mode": "all-usage"}}),
+    ];
+
+    let actual = exchange(&mut server, &requests);
+
+    assert_eq!(actual[0]["result"]["hits"], json!([]));
+    assert_ne!(actual[1]["result"]["hits"], json!([]));
+}
+
+#[test]
+/// Asking for auto mode explicitly should search every language, whatever the project's is
+fn serve_explicit_auto() {
+    let mut server = server(&["--lang", "auto"]).with_project(Project {
+        languages: vec![Language::Rust],
+    });
+    let requests =
+        [json!({"id": 1, "method": "search", "params": {"term": "Cli", "mode": "class"}})];
+
+    let actual = exchange(&mut server, &requests);
+
+    assert_eq!(
+        actual[0]["result"]["hits"][0]["filename"],
+        json!("test/fixtures/python/cli.py")
+    );
+}
+
+#[test]
+fn serve_errors() {
+    let mut server = server(&[]);
+    let requests = [
+        json!({"id": 1, "method": "frobnicate"}),
+        json!({"id": 2, "method": "search", "params": {"mode": "class"}}),
+        json!({"id": 3, "method": "search", "params": {"term": "Cli", "output_style": "import"}}),
+    ];
+
+    let actual = exchange(&mut server, &requests);
+    let codes: Vec<&Value> = actual.iter().map(|r| &r["error"]["code"]).collect();
+
+    assert_eq!(
+        codes,
+        vec![
+            &json!(METHOD_NOT_FOUND),
+            &json!(INVALID_PARAMS),
+            &json!(INVALID_PARAMS)
+        ]
+    );
+}
+
+#[test]
+/// Garbage shouldn't bring the server down
+fn serve_malformed_request() {
+    let mut server = server(&[]);
+    let input =
+        "{not json\n{\"id\": 7, \"method\": \"shutdown\"}\n{\"id\": 8, \"method\": \"search\"}\n";
+    let mut output = vec![];
+
+    server.run(input.as_bytes(), &mut output).unwrap();
+
+    let actual: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+
+    assert_eq!(actual.len(), 2);
+    assert_eq!(actual[0]["error"]["code"], json!(PARSE_ERROR));
+    assert_eq!(
+        actual[1],
+        json!({"jsonrpc": "2.0", "id": 7, "result": null})
+    );
+}
@@ -5,7 +5,7 @@ use crate::search::Hit;
 ///   - fewest leading spaces, as top-level definitions are more likely to be broadly relevant
 ///   - concrete method definitions first, according to the (naively-determined) presence of a body
 ///   - otherwise sort by filename and line number
-pub fn sort_hits(hits: &mut Vec<Hit>, mode: &SearchMode) -> () {
+pub fn sort_hits(hits: &mut [Hit], mode: &SearchMode) {
     hits.sort_by_key(|h| {
         let mut leading_spaces = 0;
 
@@ -21,7 +21,7 @@ pub fn sort_hits(hits: &mut Vec<Hit>, mode: &SearchMode) -> () {
                 && h.text
                     .chars()
                     .last()
-                    .map(|c| vec!['{', ':', '='].contains(&c))
+                    .map(|c| ['{', ':', '='].contains(&c))
                     .unwrap_or(false)
         };
 
@@ -29,7 +29,7 @@ pub fn sort_hits(hits: &mut Vec<Hit>, mode: &SearchMode) -> () {
             leading_spaces,
             has_body,
             h.filename.clone(),
-            h.line.clone().unwrap_or(0),
+            h.line.unwrap_or(0),
         )
     });
 }
@@ -0,0 +1,33 @@
+#ifndef GEOMETRY_H
+#define GEOMETRY_H
+
+#include <stddef.h>
+
+#define MAX_VERTICES 64
+#define SQUARE(x) ((x) * (x))
+
+typedef struct {
+    double x;
+    double y;
+} Vertex;
+
+typedef unsigned long vertex_count_t;
+
+typedef int (*vertex_visitor)(const Vertex *p, void *ctx);
+
+struct polygon {
+    Vertex vertices[MAX_VERTICES];
+    size_t count;
+};
+
+enum winding { CLOCKWISE, ANTICLOCKWISE };
+
+union number {
+    long i;
+    double d;
+};
+
+double distance(const Vertex *a, const Vertex *b);
+int polygon_visit(const struct polygon *poly, vertex_visitor visit, void *ctx);
+
+#endif
@@ -0,0 +1,32 @@
+#include <math.h>
+#include <stdio.h>
+
+#include "../include/geometry.h"
+
+double distance(const Vertex *a, const Vertex *b)
+{
+    return sqrt(SQUARE(a->x - b->x) + SQUARE(a->y - b->y));
+}
+
+static int
+print_vertex(const Vertex *p, void *ctx)
+{
+    FILE *out = ctx;
+    return fprintf(out, "(%f, %f)\n", p->x, p->y);
+}
+
+int polygon_visit(const struct polygon *poly, vertex_visitor visit, void *ctx)
+{
+    for (size_t i = 0; i < poly->count; i++) {
+        int res = visit(&poly->vertices[i], ctx);
+        if (res < 0) {
+            return res;
+        }
+    }
+    return 0;
+}
+
+int polygon_print(const struct polygon *poly)
+{
+    return polygon_visit(poly, print_vertex, stdout);
+}
@@ -0,0 +1,14 @@
+#include <iostream>
+#include <memory>
+
+#include "shapes/shape.hpp"
+
+int main()
+{
+    shapes::ShapeList list;
+    list.push_back(std::make_unique<shapes::Circle>(2.0));
+
+    for (const auto &s : list) {
+        std::cout << s->describe() << std::endl;
+    }
+}
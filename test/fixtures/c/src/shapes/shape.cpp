@@ -0,0 +1,22 @@
+#include "shape.hpp"
+
+#include <cmath>
+#include <sstream>
+
+namespace shapes {
+
+std::string Shape::describe() const
+{
+    std::ostringstream out;
+    out << "shape with area " << area();
+    return out.str();
+}
+
+Circle::Circle(double radius) : radius_(radius) {}
+
+double Circle::area() const
+{
+    return M_PI * radius_ * radius_;
+}
+
+}  // namespace shapes
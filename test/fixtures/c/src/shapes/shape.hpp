@@ -0,0 +1,29 @@
+#pragma once
+
+#include <memory>
+#include <string>
+#include <vector>
+
+namespace shapes {
+
+class Shape {
+public:
+    virtual ~Shape() = default;
+    virtual double area() const = 0;
+    std::string describe() const;
+};
+
+class Circle final : public Shape {
+public:
+    explicit Circle(double radius);
+    double area() const override;
+
+private:
+    double radius_;
+};
+
+enum class Unit { Metres, Feet };
+
+using ShapeList = std::vector<std::unique_ptr<Shape>>;
+
+}  // namespace shapes
@@ -0,0 +1,23 @@
+package cache
+
+import (
+	"os"
+
+	_ "github.com/lib/pq"
+	. "github.com/onsi/gomega"
+	hm "github.com/giftig/hellgo/models"
+	"gopkg.in/yaml.v3"
+)
+
+func LoadLemming(path string) (hm.Lemming, error) {
+	var lem hm.Lemming
+
+	data, err := os.ReadFile(path)
+	if err != nil {
+		return lem, err
+	}
+
+	err = yaml.Unmarshal(data, &lem)
+	Expect(err).To(BeNil())
+	return lem, err
+}
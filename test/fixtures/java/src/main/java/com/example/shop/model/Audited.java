@@ -0,0 +1,9 @@
+package com.example.shop.model;
+
+import java.lang.annotation.Retention;
+import java.lang.annotation.RetentionPolicy;
+
+@Retention(RetentionPolicy.RUNTIME)
+public @interface Audited {
+    String value() default "";
+}
@@ -0,0 +1,9 @@
+package com.example.shop.model;
+
+import java.math.BigDecimal;
+
+public record Money(BigDecimal amount, String currency) {
+    public Money plus(Money other) {
+        return new Money(amount.add(other.amount), currency);
+    }
+}
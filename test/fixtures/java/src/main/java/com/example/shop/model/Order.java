@@ -0,0 +1,24 @@
+package com.example.shop.model;
+
+import java.math.BigDecimal;
+import java.util.List;
+import java.util.Optional;
+
+public class Order {
+    private final List<LineItem> items;
+    private Status status = Status.PENDING;
+
+    public Order(List<LineItem> items) {
+        this.items = items;
+    }
+
+    public BigDecimal total() {
+        return items.stream().map(LineItem::price).reduce(BigDecimal.ZERO, BigDecimal::add);
+    }
+
+    public static <T extends Order> Optional<T> firstOf(List<T> orders) {
+        return orders.stream().findFirst();
+    }
+
+    public record LineItem(String sku, BigDecimal price) {}
+}
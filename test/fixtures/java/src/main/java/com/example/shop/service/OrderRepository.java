@@ -0,0 +1,9 @@
+package com.example.shop.service;
+
+import com.example.shop.model.Order;
+import com.example.shop.model.Status;
+import java.util.List;
+
+public interface OrderRepository {
+    List<Order> findByStatus(Status status);
+}
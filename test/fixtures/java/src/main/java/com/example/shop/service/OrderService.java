@@ -0,0 +1,21 @@
+package com.example.shop.service;
+
+import static java.util.Objects.requireNonNull;
+
+import com.example.shop.model.Audited;
+import com.example.shop.model.Order;
+import com.example.shop.model.Status;
+import java.util.List;
+
+@Audited("orders")
+public class OrderService {
+    private final OrderRepository repository;
+
+    public OrderService(OrderRepository repository) {
+        this.repository = requireNonNull(repository);
+    }
+
+    public List<Order> pending() {
+        return repository.findByStatus(Status.PENDING);
+    }
+}
@@ -0,0 +1,11 @@
+const express = require('express');
+const { readFile, writeFile: save } = require("fs");
+const { Button } = require('../src/components/Button')
+
+const app = express();
+
+app.get('/', (req, res) => {
+  readFile('index.html', (err, data) => res.send(err || new Button(data).label));
+});
+
+save('started', String(Date.now()), () => app.listen(3000));
@@ -0,0 +1,12 @@
+import React, { useState as useLocalState } from 'react';
+import * as path from "path";
+import {
+  Button,
+  ButtonGroup,
+} from './components/Button';
+
+export default function App() {
+  const [label] = useLocalState(path.basename(__filename));
+
+  return ButtonGroup(new Button(label));
+}
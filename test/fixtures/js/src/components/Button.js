@@ -0,0 +1,9 @@
+export class Button {
+  constructor(label) {
+    this.label = label;
+  }
+}
+
+export function ButtonGroup(...buttons) {
+  return buttons;
+}
@@ -0,0 +1,14 @@
+package com.example.notes.model
+
+import java.time.Instant
+
+typealias NoteId = Long
+
+data class Note(val id: NoteId, val title: String, val created: Instant)
+
+sealed interface NoteEvent {
+    data class Created(val note: Note) : NoteEvent
+    data class Deleted(val id: NoteId) : NoteEvent
+}
+
+enum class Colour { RED, GREEN, BLUE }
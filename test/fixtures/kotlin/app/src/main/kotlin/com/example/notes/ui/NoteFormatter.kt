@@ -0,0 +1,17 @@
+package com.example.notes.ui
+
+import com.example.notes.model.Note
+import com.example.notes.model.NoteId as Id
+import java.time.format.DateTimeFormatter
+
+object NoteFormatter {
+    private val dates = DateTimeFormatter.ISO_INSTANT
+
+    fun format(note: Note): String = "${note.title} (${dates.format(note.created)})"
+}
+
+fun Note.summary(length: Int = 20): String = title.take(length)
+
+fun <T> List<T>.secondOrNull(): T? = getOrNull(1)
+
+fun describe(id: Id): String = "note $id"
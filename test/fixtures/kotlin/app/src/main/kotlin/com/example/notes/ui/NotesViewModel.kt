@@ -0,0 +1,19 @@
+package com.example.notes.ui
+
+import com.example.notes.model.Note
+import com.example.notes.model.NoteEvent
+
+class NotesViewModel {
+    private val notes = mutableListOf<Note>()
+
+    fun handle(event: NoteEvent) {
+        when (event) {
+            is NoteEvent.Created -> notes.add(event.note)
+            is NoteEvent.Deleted -> notes.removeIf { it.id == event.id }
+        }
+    }
+
+    companion object Factory {
+        fun create(): NotesViewModel = NotesViewModel()
+    }
+}
@@ -0,0 +1,3 @@
+package shop
+
+@main def run(): Unit = println(Order())
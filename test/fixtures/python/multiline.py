@@ -0,0 +1,12 @@
+"""Imports split over several lines"""
+
+from s3_browser.paths import (
+    S3Bucket as Bucket,
+    S3Key,  # the full path within a bucket
+)
+from s3_browser.utils import \
+    human_size
+
+
+def describe(bucket: Bucket, key: S3Key, size: int) -> str:
+    return f"{bucket}/{key} ({human_size(size)})"
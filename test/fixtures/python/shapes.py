@@ -0,0 +1,17 @@
+from dataclasses import dataclass
+
+type Vector = list[float]
+
+
+@dataclass
+class Point:
+    x: float
+    y: float
+
+    def scale(self, factor: float) -> "Point":
+        return Point(self.x * factor, self.y * factor)
+
+
+def describe(point: Point) -> str:
+    # Mirrors the geometry service, where it's the String method of type Point struct
+    return f"Point({point.x}, {point.y})"
@@ -0,0 +1,11 @@
+# frozen_string_literal: true
+
+class Billing::Account < ApplicationRecord
+  has_many :invoices
+
+  attr_accessor :balance, :currency
+
+  define_method(:overdue?) do
+    invoices.any?(&:overdue?)
+  end
+end
@@ -0,0 +1,26 @@
+# frozen_string_literal: true
+
+module Billing
+  class InvoiceSync
+    attr_reader :account, :since
+
+    def self.call(account, since: nil)
+      new(account, since: since).call
+    end
+
+    def initialize(account, since: nil)
+      @account = account
+      @since = since
+    end
+
+    def call
+      invoices.each { |invoice| Ledger.record(invoice) }
+    end
+
+    private
+
+    def invoices
+      account.invoices.where("updated_at > ?", since)
+    end
+  end
+end
@@ -0,0 +1,21 @@
+use std::{
+    fmt::{self, Display},
+    sync::Arc,
+};
+
+use crate::models::{
+    library::Library as GameLibrary,
+    user::SteamUser,
+};
+
+/// A user's games, shared between handlers
+pub struct Shelf {
+    pub owner: SteamUser,
+    pub games: Arc<GameLibrary>,
+}
+
+impl Display for Shelf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}'s shelf", self.owner)
+    }
+}
@@ -0,0 +1,8 @@
+package com.xantoria.mmo.common.updates.stats
+
+import com.xantoria.mmo.common.model.stats.{
+  StatName,
+  StatValue
+}
+
+case class StatUpdateResult(name: StatName, value: StatValue)
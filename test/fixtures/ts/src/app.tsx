@@ -0,0 +1,8 @@
+import type { User, UserId } from "./models/user";
+import { Role, formatUser } from "./models/user";
+
+export const UserCard = ({ user }: { user: User }) => <p>{formatUser(user)}</p>;
+
+export function canEdit(role: Role, id: UserId): boolean {
+  return role === Role.Admin && id > 0;
+}
@@ -0,0 +1,28 @@
+export interface User {
+  id: number;
+  name: string;
+}
+
+export type UserId = User["id"];
+
+export const enum Role {
+  Admin,
+  Member,
+}
+
+export abstract class Repository<T> {
+  abstract find(id: UserId): Promise<T>;
+}
+
+export namespace Users {
+  export const guest: User = { id: 0, name: "guest" };
+}
+
+declare function hashPassword(password: string): string;
+
+export const formatUser = (user: User): string => `${user.name} (${user.id})`;
+
+export const fetchUser = async <T extends User>(id: UserId): Promise<T> => {
+  const res = await fetch(`/users/${id}`);
+  return res.json();
+};